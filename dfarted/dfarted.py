@@ -185,7 +185,7 @@ class Main(QMainWindow):
         tempfile.close()
         # compile it, þis should be fast, so no need to þread
         result = subprocess.run(
-            [self.infarter_path, "to", self.temp_file],
+            [self.infarter_path, "compile", "-O2", self.temp_file],
             capture_output=True
         )
         if result.stderr != b'':
@@ -77,6 +77,9 @@ void NatTbl::print() const
       case DF_STD:    printf("<STD>");    break;
       case DF_STD_IO: printf("<STD$io>"); break;
       case DF_STD_A:  printf("<STD$a>");  break;
+      case DF_STD_M:  printf("<STD$m>");  break;
+      case DF_STD_S:  printf("<STD$s>");  break;
+      case DF_STD_T:  printf("<STD$t>");  break;
     }
 }
 
@@ -91,6 +94,12 @@ bool NatTbl::get(key_t k, DfVal &v) const
         return df_std::io_get(k, v);
       case DF_STD_A:
         return df_std::a_get(k, v);
+      case DF_STD_M:
+        return df_std::m_get(k, v);
+      case DF_STD_S:
+        return df_std::s_get(k, v);
+      case DF_STD_T:
+        return df_std::t_get(k, v);
       default: unreachable();
     }
 }
@@ -101,6 +110,51 @@ NatFun::NatFun(NatFunTag t)
     switch (t) {
       case DF_STD_A_LEN:       this->eval = df_std::a_len;       break;
       case DF_STD_IO_READFILE: this->eval = df_std::io_readFile; break;
+      case DF_STD_EQ:          this->eval = df_std::eq;          break;
+      case DF_STD_IO_READLN:   this->eval = df_std::io_readLn;   break;
+      case DF_STD_IO_READALL:  this->eval = df_std::io_readAll;  break;
+      case DF_STD_M_SQRT:   this->eval = df_std::m_sqrt;  break;
+      case DF_STD_M_POW:    this->eval = df_std::m_pow;   break;
+      case DF_STD_M_EXP:    this->eval = df_std::m_exp;   break;
+      case DF_STD_M_LN:     this->eval = df_std::m_ln;    break;
+      case DF_STD_M_SIN:    this->eval = df_std::m_sin;   break;
+      case DF_STD_M_COS:    this->eval = df_std::m_cos;   break;
+      case DF_STD_M_TAN:    this->eval = df_std::m_tan;   break;
+      case DF_STD_M_ATAN2:  this->eval = df_std::m_atan2; break;
+      case DF_STD_M_FLOOR:  this->eval = df_std::m_floor; break;
+      case DF_STD_M_CEIL:   this->eval = df_std::m_ceil;  break;
+      case DF_STD_M_ROUND:  this->eval = df_std::m_round; break;
+      case DF_STD_M_ABS:    this->eval = df_std::m_abs;   break;
+      case DF_STD_M_MIN:    this->eval = df_std::m_min;   break;
+      case DF_STD_M_MAX:    this->eval = df_std::m_max;   break;
+      case DF_STD_S_FIND:       this->eval = df_std::s_find;       break;
+      case DF_STD_S_SPLIT:      this->eval = df_std::s_split;      break;
+      case DF_STD_S_JOIN:       this->eval = df_std::s_join;       break;
+      case DF_STD_S_TRIM:       this->eval = df_std::s_trim;       break;
+      case DF_STD_S_UPPER:      this->eval = df_std::s_upper;      break;
+      case DF_STD_S_LOWER:      this->eval = df_std::s_lower;      break;
+      case DF_STD_S_STARTSWITH: this->eval = df_std::s_startsWith; break;
+      case DF_STD_S_ENDSWITH:   this->eval = df_std::s_endsWith;   break;
+      case DF_STD_S_REPLACE:    this->eval = df_std::s_replace;    break;
+      case DF_STD_S_FROMN:      this->eval = df_std::s_fromN;      break;
+      case DF_STD_S_FROMZ:      this->eval = df_std::s_fromZ;      break;
+      case DF_STD_S_FROMR:      this->eval = df_std::s_fromR;      break;
+      case DF_STD_S_TON:        this->eval = df_std::s_toN;        break;
+      case DF_STD_S_TOZ:        this->eval = df_std::s_toZ;        break;
+      case DF_STD_S_TOR:        this->eval = df_std::s_toR;        break;
+      case DF_STD_A_LAST:    this->eval = df_std::a_last;    break;
+      case DF_STD_A_SLICE:   this->eval = df_std::a_slice;   break;
+      case DF_STD_A_COPY:    this->eval = df_std::a_copy;    break;
+      case DF_STD_A_INDEXOF: this->eval = df_std::a_indexOf; break;
+      case DF_STD_A_MAP:     this->eval = df_std::a_map;     break;
+      case DF_STD_A_FILTER:  this->eval = df_std::a_filter;  break;
+      case DF_STD_A_FOLD:    this->eval = df_std::a_fold;    break;
+      case DF_STD_T_KEYS:  this->eval = df_std::t_keys;     break;
+      case DF_STD_T_HAS:   this->eval = df_std::t_has;      break;
+      case DF_STD_T_GET:   this->eval = df_std::t_getField; break;
+      case DF_STD_T_LEN:   this->eval = df_std::t_len;      break;
+      case DF_STD_T_COPY:  this->eval = df_std::t_copy;     break;
+      case DF_STD_T_MERGE: this->eval = df_std::t_merge;    break;
     }
 }
 
@@ -109,6 +163,51 @@ void NatFun::print() const
     switch (this->tag) {
       case DF_STD_A_LEN: printf("# STD$a$len"); break;
       case DF_STD_IO_READFILE: printf("# STD$io$readFile"); break;
+      case DF_STD_EQ: printf("# STD$eq"); break;
+      case DF_STD_IO_READLN: printf("# STD$io$readLn"); break;
+      case DF_STD_IO_READALL: printf("# STD$io$readAll"); break;
+      case DF_STD_M_SQRT: printf("# STD$m$sqrt"); break;
+      case DF_STD_M_POW: printf("# STD$m$pow"); break;
+      case DF_STD_M_EXP: printf("# STD$m$exp"); break;
+      case DF_STD_M_LN: printf("# STD$m$ln"); break;
+      case DF_STD_M_SIN: printf("# STD$m$sin"); break;
+      case DF_STD_M_COS: printf("# STD$m$cos"); break;
+      case DF_STD_M_TAN: printf("# STD$m$tan"); break;
+      case DF_STD_M_ATAN2: printf("# STD$m$atan2"); break;
+      case DF_STD_M_FLOOR: printf("# STD$m$floor"); break;
+      case DF_STD_M_CEIL: printf("# STD$m$ceil"); break;
+      case DF_STD_M_ROUND: printf("# STD$m$round"); break;
+      case DF_STD_M_ABS: printf("# STD$m$abs"); break;
+      case DF_STD_M_MIN: printf("# STD$m$min"); break;
+      case DF_STD_M_MAX: printf("# STD$m$max"); break;
+      case DF_STD_S_FIND: printf("# STD$s$find"); break;
+      case DF_STD_S_SPLIT: printf("# STD$s$split"); break;
+      case DF_STD_S_JOIN: printf("# STD$s$join"); break;
+      case DF_STD_S_TRIM: printf("# STD$s$trim"); break;
+      case DF_STD_S_UPPER: printf("# STD$s$upper"); break;
+      case DF_STD_S_LOWER: printf("# STD$s$lower"); break;
+      case DF_STD_S_STARTSWITH: printf("# STD$s$startsWith"); break;
+      case DF_STD_S_ENDSWITH: printf("# STD$s$endsWith"); break;
+      case DF_STD_S_REPLACE: printf("# STD$s$replace"); break;
+      case DF_STD_S_FROMN: printf("# STD$s$fromN"); break;
+      case DF_STD_S_FROMZ: printf("# STD$s$fromZ"); break;
+      case DF_STD_S_FROMR: printf("# STD$s$fromR"); break;
+      case DF_STD_S_TON: printf("# STD$s$toN"); break;
+      case DF_STD_S_TOZ: printf("# STD$s$toZ"); break;
+      case DF_STD_S_TOR: printf("# STD$s$toR"); break;
+      case DF_STD_A_LAST: printf("# STD$a$last"); break;
+      case DF_STD_A_SLICE: printf("# STD$a$slice"); break;
+      case DF_STD_A_COPY: printf("# STD$a$copy"); break;
+      case DF_STD_A_INDEXOF: printf("# STD$a$indexOf"); break;
+      case DF_STD_A_MAP: printf("# STD$a$map"); break;
+      case DF_STD_A_FILTER: printf("# STD$a$filter"); break;
+      case DF_STD_A_FOLD: printf("# STD$a$fold"); break;
+      case DF_STD_T_KEYS: printf("# STD$t$keys"); break;
+      case DF_STD_T_HAS: printf("# STD$t$has"); break;
+      case DF_STD_T_GET: printf("# STD$t$get"); break;
+      case DF_STD_T_LEN: printf("# STD$t$len"); break;
+      case DF_STD_T_COPY: printf("# STD$t$copy"); break;
+      case DF_STD_T_MERGE: printf("# STD$t$merge"); break;
     }
 }
 
@@ -119,6 +218,18 @@ NatPro::NatPro(NatProTag t)
       case DF_STD_IO_PUT: this->exec = df_std::io_put; break;
       case DF_STD_GC:     this->exec = df_std::gc;     break;
       case DF_STD_A_EKE:  this->exec = df_std::a_eke;   break;
+      case DF_STD_IO_PUTLN:     this->exec = df_std::io_putLn;     break;
+      case DF_STD_IO_WRITEFILE: this->exec = df_std::io_writeFile; break;
+      case DF_STD_A_INSERT:  this->exec = df_std::a_insert;  break;
+      case DF_STD_A_REVERSE: this->exec = df_std::a_reverse; break;
+      case DF_STD_A_FILL:    this->exec = df_std::a_fill;    break;
+      case DF_STD_A_SORT:    this->exec = df_std::a_sort;    break;
+      case DF_STD_A_SORTBY:  this->exec = df_std::a_sortBy;  break;
+      case DF_STD_A_EACH:    this->exec = df_std::a_each;    break;
+      case DF_STD_A_POP:     this->exec = df_std::a_pop;     break;
+      case DF_STD_A_REMOVE:  this->exec = df_std::a_remove;  break;
+      case DF_STD_T_SET: this->exec = df_std::t_setField; break;
+      case DF_STD_T_DEL: this->exec = df_std::t_del;      break;
     }
 }
 
@@ -128,5 +239,17 @@ void NatPro::print() const
       case DF_STD_IO_PUT: printf("! STD$io$put"); break;
       case DF_STD_GC:     printf("! STD$gc");     break;
       case DF_STD_A_EKE:  printf("! STD$a$eke");  break;
+      case DF_STD_IO_PUTLN:     printf("! STD$io$putLn");     break;
+      case DF_STD_IO_WRITEFILE: printf("! STD$io$writeFile"); break;
+      case DF_STD_A_INSERT: printf("! STD$a$insert"); break;
+      case DF_STD_A_REVERSE: printf("! STD$a$reverse"); break;
+      case DF_STD_A_FILL: printf("! STD$a$fill"); break;
+      case DF_STD_A_SORT: printf("! STD$a$sort"); break;
+      case DF_STD_A_SORTBY: printf("! STD$a$sortBy"); break;
+      case DF_STD_A_EACH: printf("! STD$a$each"); break;
+      case DF_STD_A_POP: printf("! STD$a$pop"); break;
+      case DF_STD_A_REMOVE: printf("! STD$a$remove"); break;
+      case DF_STD_T_SET: printf("! STD$t$set"); break;
+      case DF_STD_T_DEL: printf("! STD$t$del"); break;
     }
 }
@@ -13,6 +13,9 @@ enum NatTblTag {
     DF_STD    = 0,
     DF_STD_IO = 1,
     DF_STD_A,
+    DF_STD_M,
+    DF_STD_S,
+    DF_STD_T,
 };
 
 class NatTbl {
@@ -30,10 +33,23 @@ class NatTbl {
     void print() const;
 };
 
+// þese tags are also InFarter's NatPc & NatFn discriminants
 enum NatProTag {
     DF_STD_IO_PUT = 0,
     DF_STD_GC,
     DF_STD_A_EKE,
+    DF_STD_IO_PUTLN,
+    DF_STD_IO_WRITEFILE,
+    DF_STD_A_INSERT,
+    DF_STD_A_REVERSE,
+    DF_STD_A_FILL,
+    DF_STD_A_SORT,
+    DF_STD_A_SORTBY,
+    DF_STD_A_EACH,
+    DF_STD_A_POP,
+    DF_STD_A_REMOVE,
+    DF_STD_T_SET,
+    DF_STD_T_DEL,
 };
 
 class NatPro {
@@ -48,6 +64,51 @@ class NatPro {
 enum NatFunTag {
     DF_STD_A_LEN,
     DF_STD_IO_READFILE,
+    DF_STD_EQ,
+    DF_STD_IO_READLN,
+    DF_STD_IO_READALL,
+    DF_STD_M_SQRT,
+    DF_STD_M_POW,
+    DF_STD_M_EXP,
+    DF_STD_M_LN,
+    DF_STD_M_SIN,
+    DF_STD_M_COS,
+    DF_STD_M_TAN,
+    DF_STD_M_ATAN2,
+    DF_STD_M_FLOOR,
+    DF_STD_M_CEIL,
+    DF_STD_M_ROUND,
+    DF_STD_M_ABS,
+    DF_STD_M_MIN,
+    DF_STD_M_MAX,
+    DF_STD_S_FIND,
+    DF_STD_S_SPLIT,
+    DF_STD_S_JOIN,
+    DF_STD_S_TRIM,
+    DF_STD_S_UPPER,
+    DF_STD_S_LOWER,
+    DF_STD_S_STARTSWITH,
+    DF_STD_S_ENDSWITH,
+    DF_STD_S_REPLACE,
+    DF_STD_S_FROMN,
+    DF_STD_S_FROMZ,
+    DF_STD_S_FROMR,
+    DF_STD_S_TON,
+    DF_STD_S_TOZ,
+    DF_STD_S_TOR,
+    DF_STD_A_LAST,
+    DF_STD_A_SLICE,
+    DF_STD_A_COPY,
+    DF_STD_A_INDEXOF,
+    DF_STD_A_MAP,
+    DF_STD_A_FILTER,
+    DF_STD_A_FOLD,
+    DF_STD_T_KEYS,
+    DF_STD_T_HAS,
+    DF_STD_T_GET,
+    DF_STD_T_LEN,
+    DF_STD_T_COPY,
+    DF_STD_T_MERGE,
 };
 
 class NatFun {
@@ -2,11 +2,13 @@
 
 #include <cstdio>
 #include <cstring>
+#include <cmath>
+#include <algorithm>
 #include <utility>
 #include "virmac.h"
 #include "object.h"
 #include "idents.h"
-#include "reader.h"
+#include "maitre.h"
 #include "latin1.h"
 
 #define CHECK_ARGC(name, a) do { \
@@ -29,15 +31,15 @@ static bool get(const DfIdf *i, DfVal &v)
     switch (i->get_len()) {
       case 1:
         if (i->eq("a")) RET_V(DF_STD_A);
+        if (i->eq("m")) RET_V(DF_STD_M);
+        if (i->eq("s")) RET_V(DF_STD_S);
+        if (i->eq("t")) RET_V(DF_STD_T);
         break;
       case 2:
         if (i->eq("io")) RET_V(DF_STD_IO);
         if (i->eq("gc")) RET_V(DF_STD_GC);
+        if (i->eq("eq")) RET_V(DF_STD_EQ);
         break;
-      default:
-        eput("ERROR: can't get field $");
-        i->eprint();
-        eputln("from STD");
     }
     return false;
 }
@@ -46,8 +48,16 @@ static bool io_get(const DfIdf *i, DfVal &v)
 {
     if (i->eq("put"))
         RET_V(DF_STD_IO_PUT);
+    if (i->eq("putLn"))
+        RET_V(DF_STD_IO_PUTLN);
+    if (i->eq("readLn"))
+        RET_V(DF_STD_IO_READLN);
+    if (i->eq("readAll"))
+        RET_V(DF_STD_IO_READALL);
     if (i->eq("readFile"))
         RET_V(DF_STD_IO_READFILE);
+    if (i->eq("writeFile"))
+        RET_V(DF_STD_IO_WRITEFILE);
     return false;
 }
 
@@ -65,35 +75,145 @@ static int io_put(VirMac &vm, DfVal *argv, size_t argc)
     return 1;
 }
 
+// reads `f` until `stop` (not kept) or EOF, false if noþing was read
+static bool read_until(FILE *f, int stop, DynArr<uint8_t> &buf)
+{
+    fflush(stdout); // for prompts
+    bool any = false;
+    int c;
+    while ((c = fgetc(f)) != EOF) {
+        any = true;
+        if (c == stop)
+            break;
+        buf.push((uint8_t) c);
+    }
+    return any;
+}
+
+// a new C% array, even if empty
+static ObjRef new_c_arr(cbyte_p s, size_t len)
+{
+    auto arr = maitre::alloc(OBJ_ARR);
+    new (arr.as_arr()) ArrObj(DfType::C);
+    TIL(i, len)
+        arr.as_arr()->push(DfVal(s[i]));
+    return arr;
+}
+
+// UTF-8 text into a new C% array, false if it can't be Latin-1
+static bool new_string(DynArr<uint8_t> &buf, const char *who, DfVal &ret)
+{
+    size_t len = 0;
+    if (!latin1_from_utf8(buf.begin(), buf.len(), buf.begin(), &len)) {
+        fprintf(stderr, "%s: text is not UTF-8 or not Latin-1\n", who);
+        return false;
+    }
+    ret = DfVal(new_c_arr(buf.begin(), len));
+    return true;
+}
+
+// a C% path into a NUL-terminated UTF-8 one
+static bool utf8_path(const DfVal &p, const char *who, DynArr<uint8_t> &res)
+{
+    if (!p.is_arr() || p.as.o.as_arr()->typ != DfType::C) {
+        fprintf(stderr, "%s: path is not a C%% array\n", who);
+        return false;
+    }
+    auto *arr = p.as.o.as_arr();
+    TIL(i, arr->len()) {
+        DfVal c;
+        arr->get(i, c);
+        if (latin1_is_ascii(c.as.c)) {
+            res.push(uint8_t(c.as.c));
+        } else {
+            res.push(uint8_t(192 | (c.as.c >> 6)));
+            res.push(uint8_t(128 | (c.as.c & 63)));
+        }
+    }
+    res.push(0);
+    return true;
+}
+
+static int io_readLn(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    (void) argv;
+    CHECK_ARGC("STD$io$readLn#", 0);
+    DynArr<uint8_t> buf;
+    if (!read_until(stdin, '\n', buf)) {
+        ret = DfVal(); // EOF
+        return 1;
+    }
+    if (buf.len() > 0 && buf[buf.len()-1] == '\r')
+        (void) buf.pop();
+    return new_string(buf, "STD$io$readLn", ret);
+}
+
+static int io_readAll(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    (void) argv;
+    CHECK_ARGC("STD$io$readAll#", 0);
+    DynArr<uint8_t> buf;
+    if (!read_until(stdin, EOF, buf)) {
+        ret = DfVal(); // EOF
+        return 1;
+    }
+    return new_string(buf, "STD$io$readAll", ret);
+}
+
 static int io_readFile(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
 {
     (void) vm;
-    CHECK_ARGC("STD$a$len#", 1);
-    auto &a = argv[0];
-    if (!a.is_arr() || a.as.o.as_arr()->typ != DfType::C) {
-        eputln("argument passed to STD$a$len is not a C% array");
+    CHECK_ARGC("STD$io$readFile#", 1);
+    DynArr<uint8_t> path;
+    if (!utf8_path(argv[0], "STD$io$readFile", path))
+        return 0;
+    FILE *f = fopen((const char *) path.begin(), "rb");
+    if (f == nullptr) {
+        fprintf(stderr, "STD$io$readFile: cannot open %s\n", path.begin());
+        return 0;
+    }
+    DynArr<uint8_t> buf;
+    (void) read_until(f, EOF, buf);
+    fclose(f);
+    return new_string(buf, "STD$io$readFile", ret);
+}
+
+static int io_writeFile(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$io$writeFile!", 2);
+    DynArr<uint8_t> path;
+    if (!utf8_path(argv[0], "STD$io$writeFile", path))
+        return 0;
+    auto &d = argv[1];
+    if (!d.is_arr() || !(d.as.o.as_arr()->typ == DfType::C
+        || d.as.o.as_arr()->is_empty())) {
+        eputln("STD$io$writeFile: data is not a C% array");
         return 0;
     }
-    // read_file
-    Reader r;
-    // FIXME also read Latin-1 paþs
-    ReadRes res = reader_open(a.as.o.as_arr()->as_ascii_string(), &r);
-    if (READRES_OK != res) {
-        eputln(readres_what(res));
-        reader_free(&r);
+    FILE *f = fopen((const char *) path.begin(), "wb");
+    if (f == nullptr) {
+        fprintf(stderr, "STD$io$writeFile: cannot open %s\n", path.begin());
         return 0;
     }
-    // copy it into a new C% array
-    auto arr = maitre::alloc(OBJ_ARR);
-    auto *res_arr = arr.as_arr();
-    res_arr->typ = DfType::V;
-    res_arr->is_nat = false;
-    TIL(i, r.len)
-        res_arr->push(DfVal(r.buf[i]));
-    // free
-    reader_free(&r);
-    // "return"
-    ret = DfVal(arr);
+    auto *arr = d.as.o.as_arr();
+    TIL(i, arr->len()) {
+        DfVal c;
+        arr->get(i, c);
+        latin1_fputc(c.as.c, f);
+    }
+    fclose(f);
+    return 1;
+}
+
+static int io_putLn(VirMac &vm, DfVal *argv, size_t argc)
+{
+    CHECK_ARGC("STD$io$putLn!", 1);
+    if (!io_put(vm, argv, argc))
+        return 0;
+    putchar('\n');
     return 1;
 }
 
@@ -105,12 +225,92 @@ static int gc(VirMac &vm, DfVal *argv, size_t argc)
     return 1;
 }
 
+// pairs of objects already being compared, l[i] wiþ r[i]
+struct Seen {
+    DynArr<ObjRef> l;
+    DynArr<ObjRef> r;
+};
+
+// same as infarter's dflib::funcs::deep_eq
+static bool deep_eq(const DfVal &a, const DfVal &b, Seen &seen)
+{
+    if (a.type != b.type)
+        return false;
+    if (a.type != VAL_O)
+        return a == b;
+    ObjRef x = a.as.o, y = b.as.o;
+    if (x == y)
+        return true;
+    auto ot = x.get_type();
+    if (ot != y.get_type())
+        return false;
+    // funcs, procs & native tables only by identity
+    if (ot == OBJ_FUN || ot == OBJ_PRO)
+        return false;
+    if (ot == OBJ_TBL && (x.as_tbl()->is_nat || y.as_tbl()->is_nat))
+        return false;
+    // assume equal if already being compared, so cycles end
+    TIL(i, seen.l.len()) {
+        if (seen.l[i] == x && seen.r[i] == y)
+            return true;
+    }
+    seen.l.push(ObjRef(x));
+    seen.r.push(ObjRef(y));
+    if (ot == OBJ_ARR) {
+        auto *xa = x.as_arr();
+        auto *ya = y.as_arr();
+        if (xa->typ != ya->typ || xa->len() != ya->len())
+            return false;
+        TIL(i, xa->len()) {
+            DfVal v, w;
+            xa->get(i, v);
+            ya->get(i, w);
+            if (!deep_eq(v, w, seen))
+                return false;
+        }
+        return true;
+    }
+    // user tables, fields in any order
+    auto &xt = x.as_tbl()->as.usr;
+    auto &yt = y.as_tbl()->as.usr;
+    if (xt.len() != yt.len())
+        return false;
+    for (auto it = xt.begin(); it != xt.end(); xt.next(it)) {
+        DfVal w;
+        if (!yt.get(it.key(), w) || !deep_eq(it.val(), w, seen))
+            return false;
+    }
+    return true;
+}
+
+static int eq(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$eq#", 2);
+    Seen seen;
+    ret = DfVal(deep_eq(argv[0], argv[1], seen));
+    return 1;
+}
+
 static bool a_get(const DfIdf *i, DfVal &v)
 {
-    if (i->eq("len"))
-        RET_V(DF_STD_A_LEN);
-    if (i->eq("eke"))
-        RET_V(DF_STD_A_EKE);
+    if (i->eq("len"))     RET_V(DF_STD_A_LEN);
+    if (i->eq("eke"))     RET_V(DF_STD_A_EKE);
+    if (i->eq("pop"))     RET_V(DF_STD_A_POP);
+    if (i->eq("last"))    RET_V(DF_STD_A_LAST);
+    if (i->eq("insert"))  RET_V(DF_STD_A_INSERT);
+    if (i->eq("remove"))  RET_V(DF_STD_A_REMOVE);
+    if (i->eq("slice"))   RET_V(DF_STD_A_SLICE);
+    if (i->eq("copy"))    RET_V(DF_STD_A_COPY);
+    if (i->eq("reverse")) RET_V(DF_STD_A_REVERSE);
+    if (i->eq("fill"))    RET_V(DF_STD_A_FILL);
+    if (i->eq("indexOf")) RET_V(DF_STD_A_INDEXOF);
+    if (i->eq("sort"))    RET_V(DF_STD_A_SORT);
+    if (i->eq("sortBy"))  RET_V(DF_STD_A_SORTBY);
+    if (i->eq("map"))     RET_V(DF_STD_A_MAP);
+    if (i->eq("filter"))  RET_V(DF_STD_A_FILTER);
+    if (i->eq("fold"))    RET_V(DF_STD_A_FOLD);
+    if (i->eq("each"))    RET_V(DF_STD_A_EACH);
     return false;
 }
 
@@ -150,4 +350,1278 @@ static int a_eke(VirMac &vm, DfVal *argv, size_t argc)
     return 1;
 }
 
+// þe rest of STD$a, same messages as infarter's
+
+static ArrObj * arr_arg(const DfVal &v, const char *name)
+{
+    if (!v.is_arr()) {
+        fprintf(stderr, "STD$a$%s: expected _%% argument, found %c%%\n",
+            name, (char) v.as_type());
+        return nullptr;
+    }
+    return v.as.o.as_arr();
+}
+
+static ArrObj * mut_arr_arg(const DfVal &v, const char *name)
+{
+    auto *a = arr_arg(v, name);
+    if (a != nullptr && !v.as.o.mut()) {
+        fprintf(stderr, "STD$a$%s: cannot change immutable array\n", name);
+        return nullptr;
+    }
+    return a;
+}
+
+// N% index, at most `max`
+static bool idx_arg(const DfVal &v, const char *name, uint32_t max,
+                    uint32_t &i)
+{
+    if (VAL_N != v.type) {
+        fprintf(stderr, "STD$a$%s: expected N%% index, found %c%%\n",
+            name, (char) v.as_type());
+        return false;
+    }
+    if (v.as.n > max) {
+        fprintf(stderr, "STD$a$%s: index %u out of bounds (len = %u)\n",
+            name, v.as.n, max);
+        return false;
+    }
+    i = v.as.n;
+    return true;
+}
+
+// like infarter's Array::try_push
+static bool push_elem(ArrObj *a, DfVal &&v, const char *name)
+{
+    if (VAL_V == v.type) {
+        fprintf(stderr,
+            "STD$a$%s: cannot push V%% value into an array\n", name);
+        return false;
+    }
+    DfType t = v.as_type();
+    if (AccRes::OK != a->push(std::move(v))) {
+        fprintf(stderr, "STD$a$%s: cannot push %c%% value into %c%% array\n",
+            name, (char) t, (char) a->typ);
+        return false;
+    }
+    return true;
+}
+
+// shallow
+static ObjRef copy_arr(const ArrObj *a)
+{
+    auto res = maitre::alloc(OBJ_ARR);
+    new (res.as_arr()) ArrObj(*a);
+    return res;
+}
+
+static int a_last(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$last#", 1);
+    auto *a = arr_arg(argv[0], "last");
+    if (a == nullptr)
+        return 0;
+    if (a->len() == 0) {
+        eputln("STD$a$last: empty array");
+        return 0;
+    }
+    a->get(a->len() - 1, ret);
+    return 1;
+}
+
+// from `i` to `j`, wiþout `j`
+static int a_slice(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$slice#", 3);
+    auto *a = arr_arg(argv[0], "slice");
+    uint32_t i, j;
+    if (a == nullptr
+     || !idx_arg(argv[2], "slice", a->len(), j)
+     || !idx_arg(argv[1], "slice", j, i))
+        return 0;
+    auto res = maitre::alloc(OBJ_ARR);
+    new (res.as_arr()) ArrObj(a->typ);
+    for (; i < j; ++i) {
+        DfVal v;
+        a->get(i, v);
+        res.as_arr()->push(std::move(v));
+    }
+    ret = DfVal(res);
+    return 1;
+}
+
+static int a_copy(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$copy#", 1);
+    auto *a = arr_arg(argv[0], "copy");
+    if (a == nullptr)
+        return 0;
+    ret = DfVal(copy_arr(a));
+    return 1;
+}
+
+// N% index or V, by STD$eq
+static int a_indexOf(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$indexOf#", 2);
+    auto *a = arr_arg(argv[0], "indexOf");
+    if (a == nullptr)
+        return 0;
+    ret = DfVal();
+    TIL(i, a->len()) {
+        DfVal v;
+        a->get(i, v);
+        Seen seen;
+        if (deep_eq(v, argv[1], seen)) {
+            ret = DfVal(uint32_t(i));
+            break;
+        }
+    }
+    return 1;
+}
+
+static int a_insert(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$insert!", 3);
+    auto *a = mut_arr_arg(argv[0], "insert");
+    uint32_t i, len;
+    if (a == nullptr || !idx_arg(argv[1], "insert", len = a->len(), i))
+        return 0;
+    // pushed 1st, to check þe type & make room
+    if (!push_elem(a, DfVal(argv[2]), "insert"))
+        return 0;
+    for (uint32_t j = len; j > i; --j) {
+        DfVal v;
+        a->get(j - 1, v);
+        a->set(j, std::move(v));
+    }
+    a->set(i, DfVal(argv[2]));
+    return 1;
+}
+
+static int a_pop(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$pop!", 1);
+    auto *a = mut_arr_arg(argv[0], "pop");
+    if (a == nullptr)
+        return 0;
+    DfVal last;
+    if (AccRes::OK != a->pop(last)) {
+        eputln("STD$a$pop: empty array");
+        return 0;
+    }
+    return 1;
+}
+
+// at `i`, moving þe rest to þe left
+static int a_remove(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$remove!", 2);
+    auto *a = mut_arr_arg(argv[0], "remove");
+    uint32_t i, len;
+    if (a == nullptr || !idx_arg(argv[1], "remove", len = a->len(), i))
+        return 0;
+    if (i == len) {
+        fprintf(stderr, "STD$a$remove: index %u out of bounds (len = %u)\n",
+            i, len);
+        return 0;
+    }
+    for (uint32_t j = i + 1; j < len; ++j) {
+        DfVal v;
+        a->get(j, v);
+        a->set(j - 1, std::move(v));
+    }
+    DfVal last;
+    a->pop(last);
+    return 1;
+}
+
+static int a_reverse(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$reverse!", 1);
+    auto *a = mut_arr_arg(argv[0], "reverse");
+    if (a == nullptr)
+        return 0;
+    for (uint32_t i = 0, j = a->len(); i + 1 < j; ++i, --j) {
+        DfVal x, y;
+        a->get(i, x);
+        a->get(j - 1, y);
+        a->set(i, std::move(y));
+        a->set(j - 1, std::move(x));
+    }
+    return 1;
+}
+
+static int a_fill(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$fill!", 2);
+    auto *a = mut_arr_arg(argv[0], "fill");
+    if (a == nullptr)
+        return 0;
+    auto &v = argv[1];
+    if (a->len() > 0 && (VAL_V == v.type || v.as_type() != a->typ)) {
+        fprintf(stderr, "STD$a$fill: cannot set %c%% value into %c%% array\n",
+            (char) v.as_type(), (char) a->typ);
+        return 0;
+    }
+    TIL(i, a->len())
+        a->set(i, DfVal(v));
+    return 1;
+}
+
+// same as infarter's merge_sort, stable & top-down, so any `lt` does þe
+// same comparisons in þe same order; `lt` gives -1 on error
+template<typename Lt>
+static bool merge_sort(DfVal *vs, size_t n, DfVal *tmp, Lt &lt)
+{
+    if (n < 2)
+        return true;
+    size_t nl = n / 2;
+    if (!merge_sort(vs, nl, tmp, lt) || !merge_sort(vs + nl, n - nl, tmp, lt))
+        return false;
+    size_t i = 0, j = nl, k = 0;
+    while (i < nl && j < n) {
+        int r = lt(vs[j], vs[i]);
+        if (r < 0)
+            return false;
+        tmp[k++] = r ? vs[j++] : vs[i++];
+    }
+    while (i < nl)
+        tmp[k++] = vs[i++];
+    while (j < n)
+        tmp[k++] = vs[j++];
+    TIL(x, n)
+        vs[x] = tmp[x];
+    return true;
+}
+
+// sorted wiþ `lt`, þen back into þe array, as long as it fits
+template<typename Lt>
+static bool sort_arr(ArrObj *a, Lt &lt)
+{
+    DynArr<DfVal> vs, tmp;
+    TIL(i, a->len()) {
+        DfVal v;
+        a->get(i, v);
+        vs.push(std::move(v));
+        tmp.push(DfVal());
+    }
+    if (!merge_sort(vs.begin(), vs.len(), tmp.begin(), lt))
+        return false;
+    for (uint32_t i = 0; i < vs.len() && i < a->len(); ++i)
+        a->set(i, DfVal(vs[i]));
+    return true;
+}
+
+// C% string or untyped empty
+static bool is_str(const DfVal &v)
+{
+    if (!v.is_arr())
+        return false;
+    auto t = v.as.o.as_arr()->typ;
+    return DfType::C == t || DfType::V == t;
+}
+
+static int str_lt(const ArrObj *s, const ArrObj *t)
+{
+    for (uint32_t i = 0; i < s->len() && i < t->len(); ++i) {
+        DfVal x, y;
+        s->get(i, x);
+        t->get(i, y);
+        if (x.as.c != y.as.c)
+            return x.as.c < y.as.c;
+    }
+    return s->len() < t->len();
+}
+
+static int natural_lt(const DfVal &x, const DfVal &y)
+{
+    switch (x.type) {
+      case VAL_N: return x.as.n < y.as.n;
+      case VAL_Z: return x.as.z < y.as.z;
+      case VAL_R: return x.as.r < y.as.r;
+      case VAL_C: return x.as.c < y.as.c;
+      default:    return str_lt(x.as.o.as_arr(), y.as.o.as_arr());
+    }
+}
+
+// N%, Z%, R% (but NaN), C% & strings, in þeir natural order
+static int a_sort(VirMac &vm, DfVal *argv, size_t argc)
+{
+    (void) vm;
+    CHECK_ARGC("STD$a$sort!", 1);
+    auto *a = mut_arr_arg(argv[0], "sort");
+    if (a == nullptr)
+        return 0;
+    const char *err = nullptr;
+    switch (a->typ) {
+      case DfType::N: case DfType::Z: case DfType::C: case DfType::V:
+        break;
+      case DfType::R:
+        TIL(i, a->len()) {
+            DfVal v;
+            a->get(i, v);
+            if (std::isnan(v.as.r))
+                err = "NaN";
+        }
+        break;
+      case DfType::A:
+        TIL(i, a->len()) {
+            DfVal v;
+            a->get(i, v);
+            if (!is_str(v))
+                err = "arrays oþer þan strings";
+        }
+        break;
+      default:
+        fprintf(stderr, "STD$a$sort: cannot sort %c%% values\n",
+            (char) a->typ);
+        return 0;
+    }
+    if (err != nullptr) {
+        fprintf(stderr, "STD$a$sort: cannot sort %s\n", err);
+        return 0;
+    }
+    sort_arr(a, natural_lt);
+    return 1;
+}
+
+// þe callbacks may change þe array, so þey get a snapshot, which stays on
+// þe stack for þe GC, along wiþ any result
+
+// `lt#x, y;` is T if x goes before y
+static int a_sortBy(VirMac &vm, DfVal *argv, size_t argc)
+{
+    CHECK_ARGC("STD$a$sortBy!", 2);
+    auto *a = mut_arr_arg(argv[0], "sortBy");
+    if (a == nullptr)
+        return 0;
+    auto &f = argv[1];
+    auto lt = [&vm, &f](const DfVal &x, const DfVal &y) -> int {
+        DfVal xy[2] = {x, y}, r;
+        if (ITP_OK != vm.call(f, xy, 2, r))
+            return -1;
+        if (VAL_B != r.type) {
+            fprintf(stderr, "STD$a$sortBy: expected B%% result, found %c%%\n",
+                (char) r.as_type());
+            return -1;
+        }
+        return r.as.b;
+    };
+    DfVal snap(copy_arr(a));
+    vm.push(snap);
+    // sorts þe snapshot, which holds þe same values
+    if (!sort_arr(snap.as.o.as_arr(), lt))
+        return 0;
+    auto *s = snap.as.o.as_arr();
+    for (uint32_t i = 0; i < s->len() && i < a->len(); ++i) {
+        DfVal v;
+        s->get(i, v);
+        a->set(i, std::move(v));
+    }
+    (void) vm.pop();
+    return 1;
+}
+
+static int a_map(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$a$map#", 2);
+    auto *a = arr_arg(argv[0], "map");
+    if (a == nullptr)
+        return 0;
+    DfVal snap(copy_arr(a));
+    auto res = maitre::alloc(OBJ_ARR);
+    new (res.as_arr()) ArrObj(DfType::V);
+    vm.push(snap);
+    vm.push(DfVal(res));
+    auto *s = snap.as.o.as_arr();
+    TIL(i, s->len()) {
+        DfVal x, y;
+        s->get(i, x);
+        if (ITP_OK != vm.call(argv[1], &x, 1, y)
+         || !push_elem(res.as_arr(), std::move(y), "map"))
+            return 0;
+    }
+    (void) vm.pop();
+    (void) vm.pop();
+    ret = DfVal(res);
+    return 1;
+}
+
+static int a_filter(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$a$filter#", 2);
+    auto *a = arr_arg(argv[0], "filter");
+    if (a == nullptr)
+        return 0;
+    DfVal snap(copy_arr(a));
+    auto res = maitre::alloc(OBJ_ARR);
+    new (res.as_arr()) ArrObj(a->typ);
+    vm.push(snap);
+    vm.push(DfVal(res));
+    auto *s = snap.as.o.as_arr();
+    TIL(i, s->len()) {
+        DfVal x, y;
+        s->get(i, x);
+        if (ITP_OK != vm.call(argv[1], &x, 1, y))
+            return 0;
+        if (VAL_B != y.type) {
+            fprintf(stderr, "STD$a$filter: expected B%% result, found %c%%\n",
+                (char) y.as_type());
+            return 0;
+        }
+        if (y.as.b)
+            res.as_arr()->push(std::move(x));
+    }
+    (void) vm.pop();
+    (void) vm.pop();
+    ret = DfVal(res);
+    return 1;
+}
+
+static int a_fold(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$a$fold#", 3);
+    auto *a = arr_arg(argv[0], "fold");
+    if (a == nullptr)
+        return 0;
+    DfVal snap(copy_arr(a));
+    vm.push(snap);
+    vm.push(argv[1]);
+    DfVal *acc = vm.sp - 1;
+    auto *s = snap.as.o.as_arr();
+    TIL(i, s->len()) {
+        DfVal xs[2] = {*acc, DfVal()};
+        s->get(i, xs[1]);
+        if (ITP_OK != vm.call(argv[2], xs, 2, *acc))
+            return 0;
+    }
+    ret = vm.pop();
+    (void) vm.pop();
+    return 1;
+}
+
+static int a_each(VirMac &vm, DfVal *argv, size_t argc)
+{
+    CHECK_ARGC("STD$a$each!", 2);
+    auto *a = arr_arg(argv[0], "each");
+    if (a == nullptr)
+        return 0;
+    DfVal snap(copy_arr(a));
+    vm.push(snap);
+    auto *s = snap.as.o.as_arr();
+    TIL(i, s->len()) {
+        DfVal x, r;
+        s->get(i, x);
+        if (ITP_OK != vm.call(argv[1], &x, 1, r))
+            return 0;
+    }
+    (void) vm.pop();
+    return 1;
+}
+
+static bool m_get(const DfIdf *i, DfVal &v)
+{
+    // same f32 values as Rust's consts
+    if (i->eq("pi")) {
+        v = DfVal(3.14159265358979f);
+        return true;
+    }
+    if (i->eq("e")) {
+        v = DfVal(2.71828182845905f);
+        return true;
+    }
+    if (i->eq("sqrt"))  RET_V(DF_STD_M_SQRT);
+    if (i->eq("pow"))   RET_V(DF_STD_M_POW);
+    if (i->eq("exp"))   RET_V(DF_STD_M_EXP);
+    if (i->eq("ln"))    RET_V(DF_STD_M_LN);
+    if (i->eq("sin"))   RET_V(DF_STD_M_SIN);
+    if (i->eq("cos"))   RET_V(DF_STD_M_COS);
+    if (i->eq("tan"))   RET_V(DF_STD_M_TAN);
+    if (i->eq("atan2")) RET_V(DF_STD_M_ATAN2);
+    if (i->eq("floor")) RET_V(DF_STD_M_FLOOR);
+    if (i->eq("ceil"))  RET_V(DF_STD_M_CEIL);
+    if (i->eq("round")) RET_V(DF_STD_M_ROUND);
+    if (i->eq("abs"))   RET_V(DF_STD_M_ABS);
+    if (i->eq("min"))   RET_V(DF_STD_M_MIN);
+    if (i->eq("max"))   RET_V(DF_STD_M_MAX);
+    return false;
+}
+
+// STD$m, all on R% unless said oþerwise, same as infarter's
+
+static bool m_real(const DfVal &v, const char *name, float &r)
+{
+    if (v.type != VAL_R) {
+        fprintf(stderr, "STD$m$%s: expected R%% argument, found %c%%\n",
+            name, (char) v.as_type());
+        return false;
+    }
+    r = v.as.r;
+    return true;
+}
+
+#define M_R_TO_R(name, f) \
+static int m_##name(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    (void) vm; \
+    CHECK_ARGC("STD$m$" #name "#", 1); \
+    float x; \
+    if (!m_real(argv[0], #name, x)) \
+        return 0; \
+    ret = DfVal(f(x)); \
+    return 1; \
+}
+
+#define M_RR_TO_R(name, f) \
+static int m_##name(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    (void) vm; \
+    CHECK_ARGC("STD$m$" #name "#", 2); \
+    float x, y; \
+    if (!m_real(argv[0], #name, x) || !m_real(argv[1], #name, y)) \
+        return 0; \
+    ret = DfVal(f(x, y)); \
+    return 1; \
+}
+
+// rounded R% into a Z%, erring if out of range like þe cast
+#define M_R_TO_Z(name, f) \
+static int m_##name(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    (void) vm; \
+    CHECK_ARGC("STD$m$" #name "#", 1); \
+    float x; \
+    if (!m_real(argv[0], #name, x)) \
+        return 0; \
+    x = f(x); \
+    if (!(x >= -2147483648.0f && x < 2147483648.0f)) { \
+        eputln("STD$m$" #name ": R% value out of Z% range"); \
+        return 0; \
+    } \
+    ret = DfVal((int32_t) x); \
+    return 1; \
+}
+
+M_R_TO_R(sqrt, std::sqrt)
+M_RR_TO_R(pow, std::pow)
+M_R_TO_R(exp, std::exp)
+M_R_TO_R(ln, std::log)
+M_R_TO_R(sin, std::sin)
+M_R_TO_R(cos, std::cos)
+M_R_TO_R(tan, std::tan)
+M_RR_TO_R(atan2, std::atan2)
+M_R_TO_Z(floor, std::floor)
+M_R_TO_Z(ceil, std::ceil)
+M_R_TO_Z(round, std::round)
+
+#undef M_R_TO_R
+#undef M_RR_TO_R
+#undef M_R_TO_Z
+
+// Z% or R%
+static int m_abs(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$m$abs#", 1);
+    auto &a = argv[0];
+    switch (a.type) {
+      case VAL_Z:
+        if (a.as.z == INT32_MIN) {
+            fprintf(stderr, "STD$m$abs: Z%% value %d has no Z%% absolute "
+                "value\n", a.as.z);
+            return 0;
+        }
+        ret = DfVal((int32_t) (a.as.z < 0 ? -a.as.z : a.as.z));
+        return 1;
+      case VAL_R:
+        ret = DfVal(std::fabs(a.as.r));
+        return 1;
+      default:
+        fprintf(stderr, "STD$m$abs: expected Z%% or R%% argument, "
+            "found %c%%\n", (char) a.as_type());
+        return 0;
+    }
+}
+
+// boþ N%, Z% or R%
+static int m_min_max(DfVal *argv, const char *name, bool max, DfVal &ret)
+{
+    auto &x = argv[0], &y = argv[1];
+    if (x.type != y.type) {
+        fprintf(stderr, "STD$m$%s: expected two N%%, Z%% or R%% of þe same "
+            "type, found %c%% and %c%%\n", name,
+            (char) x.as_type(), (char) y.as_type());
+        return 0;
+    }
+    switch (x.type) {
+      case VAL_N:
+        ret = DfVal(max ? std::max(x.as.n, y.as.n)
+                        : std::min(x.as.n, y.as.n));
+        return 1;
+      case VAL_Z:
+        ret = DfVal(max ? std::max(x.as.z, y.as.z)
+                        : std::min(x.as.z, y.as.z));
+        return 1;
+      case VAL_R:
+        ret = DfVal(max ? std::fmax(x.as.r, y.as.r)
+                        : std::fmin(x.as.r, y.as.r));
+        return 1;
+      default:
+        fprintf(stderr, "STD$m$%s: expected two N%%, Z%% or R%% of þe same "
+            "type, found %c%% and %c%%\n", name,
+            (char) x.as_type(), (char) y.as_type());
+        return 0;
+    }
+}
+
+static int m_min(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$m$min#", 2);
+    return m_min_max(argv, "min", false, ret);
+}
+
+static int m_max(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$m$max#", 2);
+    return m_min_max(argv, "max", true, ret);
+}
+
+static bool s_get(const DfIdf *i, DfVal &v)
+{
+    if (i->eq("find"))       RET_V(DF_STD_S_FIND);
+    if (i->eq("split"))      RET_V(DF_STD_S_SPLIT);
+    if (i->eq("join"))       RET_V(DF_STD_S_JOIN);
+    if (i->eq("trim"))       RET_V(DF_STD_S_TRIM);
+    if (i->eq("upper"))      RET_V(DF_STD_S_UPPER);
+    if (i->eq("lower"))      RET_V(DF_STD_S_LOWER);
+    if (i->eq("startsWith")) RET_V(DF_STD_S_STARTSWITH);
+    if (i->eq("endsWith"))   RET_V(DF_STD_S_ENDSWITH);
+    if (i->eq("replace"))    RET_V(DF_STD_S_REPLACE);
+    if (i->eq("fromN"))      RET_V(DF_STD_S_FROMN);
+    if (i->eq("fromZ"))      RET_V(DF_STD_S_FROMZ);
+    if (i->eq("fromR"))      RET_V(DF_STD_S_FROMR);
+    if (i->eq("toN"))        RET_V(DF_STD_S_TON);
+    if (i->eq("toZ"))        RET_V(DF_STD_S_TOZ);
+    if (i->eq("toR"))        RET_V(DF_STD_S_TOR);
+    return false;
+}
+
+// STD$s, strings are C% arrays (or untyped empty ones), same as infarter's
+
+// þe bytes pushed into s, false if v isn't a string
+static bool str_bytes(const DfVal &v, DynArr<uint8_t> &s)
+{
+    if (!v.is_arr())
+        return false;
+    auto *a = v.as.o.as_arr();
+    if (DfType::C != a->typ && DfType::V != a->typ)
+        return false;
+    TIL(i, a->len()) {
+        DfVal c;
+        a->get(i, c);
+        s.push(uint8_t(c.as.c));
+    }
+    return true;
+}
+
+static bool s_str(const DfVal &v, const char *name, DynArr<uint8_t> &s)
+{
+    if (str_bytes(v, s))
+        return true;
+    fprintf(stderr, "STD$s$%s: expected C%% string, found %c%%\n",
+        name, (char) v.as_type());
+    return false;
+}
+
+static void s_push(DynArr<uint8_t> &s, cbyte_p p, size_t len)
+{
+    TIL(i, len)
+        s.push(uint8_t(p[i]));
+}
+
+// index of þe 1st `sub` in `s` from `i` on, or -1
+static int64_t find_from(const DynArr<uint8_t> &s,
+                         const DynArr<uint8_t> &sub, size_t i)
+{
+    for (; i + sub.len() <= s.len(); ++i) {
+        if (0 == memcmp(&s[i], sub.begin(), sub.len()))
+            return (int64_t) i;
+    }
+    return -1;
+}
+
+static bool is_space(uint8_t c)
+{
+    return c == ' ' || c == '\t' || c == '\n' || c == '\r';
+}
+
+// ß & ÿ have no Latin-1 uppercase
+static uint8_t latin1_upper(uint8_t c)
+{
+    if ((c >= 'a' && c <= 'z') || (c >= 0xE0 && c <= 0xFE && c != 0xF7))
+        return c - 32;
+    return c;
+}
+
+static uint8_t latin1_lower(uint8_t c)
+{
+    if ((c >= 'A' && c <= 'Z') || (c >= 0xC0 && c <= 0xDE && c != 0xD7))
+        return c + 32;
+    return c;
+}
+
+#define S_ARGS(name, n) \
+    (void) vm; \
+    CHECK_ARGC("STD$s$" name "#", n);
+// N% index or V
+static int s_find(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("find", 2)
+    DynArr<uint8_t> s, sub;
+    if (!s_str(argv[0], "find", s) || !s_str(argv[1], "find", sub))
+        return 0;
+    int64_t i = find_from(s, sub, 0);
+    ret = i < 0 ? DfVal() : DfVal((uint32_t) i);
+    return 1;
+}
+
+// an empty `sep` splits by whitespace, wiþout empty strings
+static int s_split(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("split", 2)
+    DynArr<uint8_t> s, sep;
+    if (!s_str(argv[0], "split", s) || !s_str(argv[1], "split", sep))
+        return 0;
+    auto arr = maitre::alloc(OBJ_ARR);
+    new (arr.as_arr()) ArrObj(DfType::A);
+    size_t i = 0;
+    if (sep.is_empty()) {
+        while (i < s.len()) {
+            size_t j = i;
+            while (j < s.len() && !is_space(s[j]))
+                ++j;
+            if (j > i)
+                arr.as_arr()->push(DfVal(new_c_arr(&s[i], j - i)));
+            i = j + 1;
+        }
+    } else {
+        int64_t j;
+        while ((j = find_from(s, sep, i)) >= 0) {
+            arr.as_arr()->push(DfVal(new_c_arr(&s[i], j - i)));
+            i = j + sep.len();
+        }
+        arr.as_arr()->push(DfVal(new_c_arr(&s[i], s.len() - i)));
+    }
+    ret = DfVal(arr);
+    return 1;
+}
+
+static int s_join(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("join", 2)
+    DynArr<uint8_t> sep, res;
+    if (!s_str(argv[1], "join", sep))
+        return 0;
+    auto &ps = argv[0];
+    if (!ps.is_arr() || !(DfType::A == ps.as.o.as_arr()->typ
+        || ps.as.o.as_arr()->is_empty())) {
+        fprintf(stderr, "STD$s$join: expected array of C%% strings, "
+            "found %c%%\n", (char) ps.as_type());
+        return 0;
+    }
+    auto *a = ps.as.o.as_arr();
+    TIL(i, a->len()) {
+        DfVal p;
+        a->get(i, p);
+        if (i > 0)
+            s_push(res, sep.begin(), sep.len());
+        if (!s_str(p, "join", res))
+            return 0;
+    }
+    ret = DfVal(new_c_arr(res.begin(), res.len()));
+    return 1;
+}
+
+static int s_trim(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("trim", 1)
+    DynArr<uint8_t> s;
+    if (!s_str(argv[0], "trim", s))
+        return 0;
+    size_t beg = 0, end = s.len();
+    while (beg < end && is_space(s[beg]))
+        ++beg;
+    while (end > beg && is_space(s[end-1]))
+        --end;
+    ret = DfVal(new_c_arr(s.begin() + beg, end - beg));
+    return 1;
+}
+
+#define S_MAP(name, f) \
+static int s_##name(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    S_ARGS(#name, 1) \
+    DynArr<uint8_t> s; \
+    if (!s_str(argv[0], #name, s)) \
+        return 0; \
+    for (auto &c : s) \
+        c = f(c); \
+    ret = DfVal(new_c_arr(s.begin(), s.len())); \
+    return 1; \
+}
+
+S_MAP(upper, latin1_upper)
+S_MAP(lower, latin1_lower)
+
+#undef S_MAP
+
+static int s_starts_ends(DfVal *argv, const char *name, bool start,
+                         DfVal &ret)
+{
+    DynArr<uint8_t> s, fix;
+    if (!s_str(argv[0], name, s) || !s_str(argv[1], name, fix))
+        return 0;
+    bool res = fix.len() <= s.len() && 0 == memcmp(
+        start ? s.begin() : s.end() - fix.len(), fix.begin(), fix.len());
+    ret = DfVal(res);
+    return 1;
+}
+
+static int s_startsWith(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("startsWith", 2)
+    return s_starts_ends(argv, "startsWith", true, ret);
+}
+
+static int s_endsWith(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("endsWith", 2)
+    return s_starts_ends(argv, "endsWith", false, ret);
+}
+
+// all of þem, from left to right
+static int s_replace(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    S_ARGS("replace", 3)
+    DynArr<uint8_t> s, from, to, res;
+    if (!s_str(argv[0], "replace", s) || !s_str(argv[1], "replace", from)
+        || !s_str(argv[2], "replace", to))
+        return 0;
+    if (from.is_empty()) {
+        eputln("STD$s$replace: cannot replace ''");
+        return 0;
+    }
+    size_t i = 0;
+    int64_t j;
+    while ((j = find_from(s, from, i)) >= 0) {
+        s_push(res, &s[i], j - i);
+        s_push(res, to.begin(), to.len());
+        i = j + from.len();
+    }
+    s_push(res, &s[i], s.len() - i);
+    ret = DfVal(new_c_arr(res.begin(), res.len()));
+    return 1;
+}
+
+// like Rust's Display for f32: þe shortest digits þat read back þe same,
+// wiþout exponent
+static void fmt_real(float r, DynArr<uint8_t> &s)
+{
+    const char *w = nullptr;
+    if (std::isnan(r))
+        w = "NaN";
+    else if (std::isinf(r))
+        w = r < 0 ? "-inf" : "inf";
+    if (w != nullptr) {
+        s_push(s, (cbyte_p) w, strlen(w));
+        return;
+    }
+    if (std::signbit(r)) {
+        s.push('-');
+        r = -r;
+    }
+    // d.ddde[+-]x, 9 digits always read back
+    char buf[32];
+    int p;
+    for (p = 1; p < 9; ++p) {
+        snprintf(buf, sizeof buf, "%.*e", p - 1, r);
+        if (strtof(buf, nullptr) == r)
+            break;
+    }
+    if (p == 9)
+        snprintf(buf, sizeof buf, "%.*e", p - 1, r);
+    char digs[9];
+    digs[0] = buf[0];
+    TIL(i, (size_t) p - 1)
+        digs[i+1] = buf[i+2];
+    int e = atoi(strchr(buf, 'e') + 1);
+    if (e < 0) {
+        s_push(s, (cbyte_p) "0.", 2);
+        TIL(i, (size_t) (-e - 1))
+            s.push('0');
+        s_push(s, (cbyte_p) digs, p);
+        return;
+    }
+    for (int i = 0; i <= e; ++i)
+        s.push(uint8_t(i < p ? digs[i] : '0'));
+    if (p > e + 1) {
+        s.push('.');
+        s_push(s, (cbyte_p) digs + e + 1, p - e - 1);
+    }
+}
+
+static int s_from(DfVal *argv, DfType t, DfVal &ret)
+{
+    auto &x = argv[0];
+    if (x.as_type() != t) {
+        fprintf(stderr, "STD$s$from%c: expected %c%% argument, found %c%%\n",
+            (char) t, (char) t, (char) x.as_type());
+        return 0;
+    }
+    DynArr<uint8_t> s;
+    char b[16];
+    switch (t) {
+      case DfType::N:
+        s_push(s, (cbyte_p) b, snprintf(b, sizeof b, "%u", x.as.n));
+        break;
+      case DfType::Z:
+        s_push(s, (cbyte_p) b, snprintf(b, sizeof b, "%d", x.as.z));
+        break;
+      default:
+        fmt_real(x.as.r, s);
+    }
+    ret = DfVal(new_c_arr(s.begin(), s.len()));
+    return 1;
+}
+
+#define S_FROM(X) \
+static int s_from##X(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    S_ARGS("from" #X, 1) \
+    return s_from(argv, DfType::X, ret); \
+}
+
+S_FROM(N)
+S_FROM(Z)
+S_FROM(R)
+
+#undef S_FROM
+
+// like þe cast, but V if it isn't a number
+#define S_TO(X) \
+static int s_to##X(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret) \
+{ \
+    S_ARGS("to" #X, 1) \
+    DynArr<uint8_t> s; \
+    if (!s_str(argv[0], "to" #X, s)) \
+        return 0; \
+    DfVal v = argv[0]; \
+    ret = nullptr == cast_val(v, DfType::X) ? v : DfVal(); \
+    return 1; \
+}
+
+S_TO(N)
+S_TO(Z)
+S_TO(R)
+
+#undef S_TO
+#undef S_ARGS
+
+static bool t_get(const DfIdf *i, DfVal &v)
+{
+    if (i->eq("keys"))  RET_V(DF_STD_T_KEYS);
+    if (i->eq("has"))   RET_V(DF_STD_T_HAS);
+    if (i->eq("get"))   RET_V(DF_STD_T_GET);
+    if (i->eq("set"))   RET_V(DF_STD_T_SET);
+    if (i->eq("del"))   RET_V(DF_STD_T_DEL);
+    if (i->eq("len"))   RET_V(DF_STD_T_LEN);
+    if (i->eq("copy"))  RET_V(DF_STD_T_COPY);
+    if (i->eq("merge")) RET_V(DF_STD_T_MERGE);
+    return false;
+}
+
+// STD$t, for user tables & also native ones when þey're not changed
+
+// same order as infarter's KEYS in each dfstd module, tblLib.df checks
+// þat þey resolve & agree.sh þat boþ lists match
+static const char *const * nat_keys(NatTblTag tag, size_t &n)
+{
+    static const char *const std_keys[] = {
+        "io", "a", "m", "s", "t", "eq", "gc"};
+    static const char *const io_keys[] = {
+        "put", "putLn", "readLn", "readAll", "readFile", "writeFile"};
+    static const char *const a_keys[] = {
+        "eke", "len", "pop", "last", "insert", "remove", "slice", "copy",
+        "reverse", "fill", "indexOf", "sort", "sortBy", "map", "filter",
+        "fold", "each"};
+    static const char *const m_keys[] = {
+        "pi", "e", "sqrt", "pow", "exp", "ln", "sin", "cos", "tan", "atan2",
+        "floor", "ceil", "round", "abs", "min", "max"};
+    static const char *const s_keys[] = {
+        "find", "split", "join", "trim", "upper", "lower", "startsWith",
+        "endsWith", "replace", "fromN", "fromZ", "fromR", "toN", "toZ", "toR"};
+    static const char *const t_keys[] = {
+        "keys", "has", "get", "set", "del", "len", "copy", "merge"};
+#define KEYS(ks) do { \
+    n = sizeof(ks) / sizeof(*ks); \
+    return ks; \
+} while (false)
+    switch (tag) {
+      case DF_STD:    KEYS(std_keys);
+      case DF_STD_IO: KEYS(io_keys);
+      case DF_STD_A:  KEYS(a_keys);
+      case DF_STD_M:  KEYS(m_keys);
+      case DF_STD_S:  KEYS(s_keys);
+      case DF_STD_T:  KEYS(t_keys);
+      default: unreachable();
+    }
+#undef KEYS
+}
+
+static const char * nat_name(NatTblTag tag)
+{
+    switch (tag) {
+      case DF_STD:    return "STD";
+      case DF_STD_IO: return "STD$io";
+      case DF_STD_A:  return "STD$a";
+      case DF_STD_M:  return "STD$m";
+      case DF_STD_S:  return "STD$s";
+      case DF_STD_T:  return "STD$t";
+      default: unreachable();
+    }
+}
+
+// keys made at runtime, not in þe program's idents, live until exit
+static struct RtIdfs {
+    DynArr<DfIdf *> v;
+    ~RtIdfs() {
+        TIL(i, this->v.len())
+            delete this->v[i];
+    }
+} rt_idfs;
+
+// þe ident wiþ þose bytes, made if `make` & þere's none yet
+static const DfIdf * intern(VirMac &vm, cbyte_p s, size_t len, bool make)
+{
+    auto &idf = vm.dat->idf;
+    TIL(i, idf.len()) {
+        if (idf[i].eq(s, len))
+            return &idf[i];
+    }
+    TIL(i, rt_idfs.v.len()) {
+        if (rt_idfs.v[i]->eq(s, len))
+            return rt_idfs.v[i];
+    }
+    if (!make)
+        return nullptr;
+    rt_idfs.v.push(new DfIdf(s, len));
+    return rt_idfs.v[rt_idfs.v.len() - 1];
+}
+
+static TblObj * tbl_arg(const DfVal &v, const char *name)
+{
+    if (!v.is_tbl()) {
+        fprintf(stderr, "STD$t$%s: expected $%% argument, found %c%%\n",
+            name, (char) v.as_type());
+        return nullptr;
+    }
+    return v.as.o.as_tbl();
+}
+
+static TblObj * usr_tbl_arg(const DfVal &v, const char *name)
+{
+    auto *t = tbl_arg(v, name);
+    if (t == nullptr)
+        return nullptr;
+    if (t->is_nat) {
+        fprintf(stderr, "STD$t$%s: cannot change native table %s\n",
+            name, nat_name(t->as.nat.tag));
+        return nullptr;
+    }
+    if (!v.as.o.mut()) {
+        fprintf(stderr, "STD$t$%s: cannot change immutable table\n", name);
+        return nullptr;
+    }
+    return t;
+}
+
+// any C% string, even if it's not an identifier
+static bool key_arg(const DfVal &v, const char *name, DynArr<uint8_t> &k)
+{
+    if (str_bytes(v, k))
+        return true;
+    fprintf(stderr, "STD$t$%s: expected C%% key, found %c%%\n",
+        name, (char) v.as_type());
+    return false;
+}
+
+// false if it's not þere
+static bool tbl_get(VirMac &vm, TblObj *t, const DynArr<uint8_t> &k,
+                    DfVal &v)
+{
+    if (t->is_nat) {
+        DfIdf i(k.begin(), k.len());
+        return t->as.nat.get(&i, v);
+    }
+    auto *i = intern(vm, k.begin(), k.len(), false);
+    return i != nullptr && t->as.usr.get(i, v);
+}
+
+// as a _% array of C% strings
+static int t_keys(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$t$keys#", 1);
+    auto *t = tbl_arg(argv[0], "keys");
+    if (t == nullptr)
+        return 0;
+    auto res = maitre::alloc(OBJ_ARR);
+    new (res.as_arr()) ArrObj(DfType::A);
+    if (t->is_nat) {
+        size_t n;
+        auto ks = nat_keys(t->as.nat.tag, n);
+        TIL(i, n) {
+            auto k = new_c_arr((cbyte_p) ks[i], std::strlen(ks[i]));
+            res.as_arr()->push(DfVal(k));
+        }
+    } else {
+        auto &u = t->as.usr;
+        for (auto it = u.begin(); it != u.end(); u.next(it)) {
+            auto k = new_c_arr(it.key()->get_str(), it.key()->get_len());
+            res.as_arr()->push(DfVal(k));
+        }
+    }
+    ret = DfVal(res);
+    return 1;
+}
+
+static int t_has(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$t$has#", 2);
+    auto *t = tbl_arg(argv[0], "has");
+    DynArr<uint8_t> k;
+    if (t == nullptr || !key_arg(argv[1], "has", k))
+        return 0;
+    DfVal v;
+    ret = DfVal(tbl_get(vm, t, k, v));
+    return 1;
+}
+
+// V if it's not þere
+static int t_getField(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$t$get#", 2);
+    auto *t = tbl_arg(argv[0], "get");
+    DynArr<uint8_t> k;
+    if (t == nullptr || !key_arg(argv[1], "get", k))
+        return 0;
+    if (!tbl_get(vm, t, k, ret))
+        ret = DfVal();
+    else if (!argv[0].as.o.mut()) // propagate mutability, as OP_TGF
+        ret.set_mut(false);
+    return 1;
+}
+
+static int t_setField(VirMac &vm, DfVal *argv, size_t argc)
+{
+    CHECK_ARGC("STD$t$set!", 3);
+    auto *t = usr_tbl_arg(argv[0], "set");
+    DynArr<uint8_t> k;
+    if (t == nullptr || !key_arg(argv[1], "set", k))
+        return 0;
+    auto *i = intern(vm, k.begin(), k.len(), true);
+    t->as.usr.set(i, DfVal(argv[2]));
+    return 1;
+}
+
+// noþing if it's not þere
+static int t_del(VirMac &vm, DfVal *argv, size_t argc)
+{
+    CHECK_ARGC("STD$t$del!", 2);
+    auto *t = usr_tbl_arg(argv[0], "del");
+    DynArr<uint8_t> k;
+    if (t == nullptr || !key_arg(argv[1], "del", k))
+        return 0;
+    auto *i = intern(vm, k.begin(), k.len(), false);
+    if (i != nullptr)
+        t->as.usr.del(i);
+    return 1;
+}
+
+static int t_len(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    (void) vm;
+    CHECK_ARGC("STD$t$len#", 1);
+    auto *t = tbl_arg(argv[0], "len");
+    if (t == nullptr)
+        return 0;
+    size_t n;
+    if (t->is_nat)
+        (void) nat_keys(t->as.nat.tag, n);
+    else
+        n = t->as.usr.len();
+    ret = DfVal(uint32_t(n));
+    return 1;
+}
+
+// þe fields of `t` into þe user table `res`, over þe ones it has
+static void tbl_into(VirMac &vm, TblObj *t, Htable &res)
+{
+    if (t->is_nat) {
+        size_t n;
+        auto ks = nat_keys(t->as.nat.tag, n);
+        TIL(i, n) {
+            auto *k = intern(vm, (cbyte_p) ks[i], std::strlen(ks[i]), true);
+            DfVal v;
+            t->as.nat.get(k, v);
+            res.set(k, std::move(v));
+        }
+        return;
+    }
+    auto &u = t->as.usr;
+    for (auto it = u.begin(); it != u.end(); u.next(it))
+        res.set(it.key(), DfVal(it.val()));
+}
+
+// shallow, & a user table even if `t` is native
+static int t_copy(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$t$copy#", 1);
+    auto *t = tbl_arg(argv[0], "copy");
+    if (t == nullptr)
+        return 0;
+    auto res = maitre::alloc(OBJ_TBL);
+    res.as_tbl()->set(Htable());
+    tbl_into(vm, t, res.as_tbl()->as.usr);
+    ret = DfVal(res);
+    return 1;
+}
+
+static int t_merge(VirMac &vm, DfVal *argv, size_t argc, DfVal &ret)
+{
+    CHECK_ARGC("STD$t$merge#", 2);
+    auto *t = tbl_arg(argv[0], "merge");
+    auto *u = t == nullptr ? nullptr : tbl_arg(argv[1], "merge");
+    if (u == nullptr)
+        return 0;
+    auto res = maitre::alloc(OBJ_TBL);
+    res.as_tbl()->set(Htable());
+    tbl_into(vm, t, res.as_tbl()->as.usr);
+    tbl_into(vm, u, res.as_tbl()->as.usr);
+    ret = DfVal(res);
+    return 1;
+}
+
 }; // namespace df_std
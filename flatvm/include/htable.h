@@ -31,8 +31,10 @@ class Htable {
     Htable();
     Htable(Htable &&);
     ~Htable();
+    size_t len() const;
     bool get(key_t, DfVal &) const;
     bool set(key_t, DfVal &&);
+    bool del(key_t);
     void print() const;
     HtIter begin();
     HtIter end() const;
@@ -18,9 +18,11 @@ class DfIdf {
     ~DfIdf();
     uint32_t get_hash() const;
     size_t get_len() const;
+    cbyte_p get_str() const;
     void print() const;
     void eprint() const;
     bool eq(const char *) const;
+    bool eq(cbyte_p, size_t) const;
     DfIdf & operator=(DfIdf &&);
 };
 
@@ -3,6 +3,7 @@
 #ifndef FLATVM_LATIN1_H
 #define FLATVM_LATIN1_H
 
+#include <stdio.h>
 #include "common.h"
 
 #ifdef __cplusplus
@@ -13,6 +14,9 @@ int  latin1_is_ascii       (uint8_t);
 int  latin1_is_ascii_string(cbyte_p, size_t);
 void latin1_putchar        (uint8_t);
 void latin1_print          (cbyte_p, size_t);
+void latin1_fputc          (uint8_t, FILE *);
+void latin1_fprint         (FILE *, cbyte_p, size_t);
+int  latin1_from_utf8      (cbyte_p, size_t, uint8_t *, size_t *);
 
 #ifdef __cplusplus
 } // extern C
@@ -7,12 +7,16 @@
 #include "values.h"
 #include "norris.h"
 
+// header flags, þe byte after þe magic
+#define DF_FLAG_WRAP 0x01 // wrapping integer ariþmetic
+
 class VmData {
   public:
     // owns everyþing in þe dynarrs
     DynArr<DfIdf>  idf;
     DynArr<DfVal>  ctn;
     DynArr<Norris> pag;
+    bool wrap; // N% & Z% overflows wrap around instead of failing
     // meþods
     VmData(const uint8_t *, size_t);
     ~VmData();
@@ -32,6 +32,7 @@ enum Op : uint8_t {
     OP_INC = 0x16,
     OP_DEC = 0x17,
     OP_MOD = 0x18,
+    OP_POW = 0x19,
 
     OP_CEQ = 0x98,
     OP_CNE = 0x99,
@@ -44,6 +45,8 @@ enum Op : uint8_t {
     OP_AND = 0x21,
     OP_IOR = 0x22,
     OP_XOR = 0x23,
+    OP_SHL = 0x24,
+    OP_SHR = 0x25,
 
     OP_LLS = 0x44,
     OP_SLS = 0x45,
@@ -52,11 +52,13 @@ class ArrObj : public Object {
     ArrObj(const ArrObj &); // copy
     ~ArrObj();
     ArrObj(DfVal &&); // array from single element, type inferred
+    ArrObj(DfType);   // empty array of a given type
     uint32_t len() const;
     bool is_empty() const;
     AccRes push(DfVal &&);
     AccRes get(uint32_t, DfVal &) const;
     AccRes set(uint32_t, DfVal &&);
+    AccRes pop(DfVal &); // keeps þe type
     AccRes concat(const ArrObj &, ArrObj &) const;
     AccRes extend(const ArrObj &);
     void print() const;
@@ -37,7 +37,7 @@ class VirMac {
 
   private: // meþods
     void reset_stack();
-    ItpRes _run();
+    ItpRes _run(int);
     void push_call(DfVal *, Norris *);
     void pop_call();
     void set_norris(Norris *);
@@ -48,6 +48,7 @@ class VirMac {
     VirMac();
     ~VirMac();
     ItpRes run(VmData *);
+    ItpRes call(const DfVal &, const DfVal *, size_t, DfVal &);
     void push(const DfVal &);
     void push(DfVal &&);
     void fpush(DfVal &&);
@@ -62,4 +63,7 @@ class VirMac {
     }
 };
 
+// casts `v` in place as in doc/values.md, nullptr or þe error message
+const char * cast_val(DfVal &, DfType);
+
 #endif /* FLATVM_VIRMAC_H */
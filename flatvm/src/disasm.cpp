@@ -25,6 +25,8 @@ void disasm_vmdata(VmData *vmd, const char *name)
     set_vmdata(vmd);
     printf("size of Norris = %u\n", (uint) sizeof(Norris));
     printf("=== %s ===\n", name);
+    if (dat->wrap)
+        puts("wrapping ariþmetic");
     size_t pag_len = dat->pag.len();
     for (size_t p = 0; p < pag_len; ++p) {
         nor = &dat->pag[p];
@@ -91,6 +93,7 @@ static void disasm_ins_fast()
       ONE(INC)
       ONE(DEC)
       ONE(MOD)
+      ONE(POW)
 
       ONE(CEQ)
       ONE(CNE)
@@ -103,7 +106,11 @@ static void disasm_ins_fast()
       ONE(AND)
       ONE(IOR)
       ONE(XOR)
+      ONE(SHL)
+      ONE(SHR)
 
+      ONE(CAB)
+      ONE(CAC)
       ONE(CAN)
       ONE(CAZ)
       ONE(CAR)
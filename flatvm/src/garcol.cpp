@@ -15,6 +15,7 @@ static void mark_roots(VirMac *);
 static void mark(Htable *);
 static void mark(TblObj *);
 static void mark(DynArr<DfVal> &);
+static void mark(ArrObj *);
 static void trace_refs();
 static void blacken_obj(ObjRef &);
 
@@ -49,11 +50,12 @@ void garcol::do_it(VirMac *vm)
 
 // static stuff --------------------------------------
 
-/* marks globals & stack */
+/* marks globals, stack & native singletons */
 static void mark_roots(VirMac *vm)
 {
     TIL(i, STACK_MAX)
         mark_dfval(vm->stack[i]);
+    NatFactory::mark_all();
 }
 
 static void mark(TblObj *t)
@@ -69,6 +71,25 @@ static void mark(Htable *t)
         mark_dfval(e.val());
 }
 
+// only arrays of objects (_%, $%, #% or !%) have neibrs
+static void mark(ArrObj *a)
+{
+    switch (a->typ) {
+      case DfType::A:
+      case DfType::T:
+      case DfType::F:
+      case DfType::P:
+        break;
+      default:
+        return;
+    }
+    TIL(i, a->len()) {
+        DfVal v;
+        a->get(i, v);
+        mark_dfval(v);
+    }
+}
+
 static void mark(DynArr<DfVal> &a)
 {
     TIL(i, a.len())
@@ -88,6 +109,7 @@ static void blacken_obj(ObjRef &obj)
 {
     switch (obj.typ()) {
       case OBJ_ARR:
+        mark(obj.as_arr());
         return;
       case OBJ_TBL:
         mark(obj.as_tbl());
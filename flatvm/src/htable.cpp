@@ -88,16 +88,19 @@ Htable::~Htable()
         free(this->ent);
 }
 
+size_t Htable::len() const
+{
+    return this->siz;
+}
+
 // return true if found k
 bool Htable::get(const DfIdf *k, DfVal &v) const
 {
     if (this->siz == 0)
         return false;
     auto e = find_entry(this->ent, this->cap, k);
-    if (e == nullptr)
+    if (e->k == nullptr) // mt slot
         return false;
-//    e->v.print();
-//    panic("wlkj");
     v = e->v;
     return true;
 }
@@ -115,6 +118,31 @@ bool Htable::set(const DfIdf *k, DfVal &&v)
     return is_new_key;
 }
 
+// return true if k was in þe table
+bool Htable::del(const DfIdf *k)
+{
+    if (this->siz == 0)
+        return false;
+    auto e = find_entry(this->ent, this->cap, k);
+    if (e->k == nullptr)
+        return false;
+    // shift back þe entries after it, so no probing stops too soon
+    size_t aux = this->cap - 1;
+    size_t i = e - this->ent;
+    for (size_t j = (i + 1) & aux; !this->ent[j].is_empty(); j = (j+1) & aux) {
+        auto &f = this->ent[j];
+        size_t h = f.k->get_hash() & aux; // where f would be, if free
+        // f stays if h is cyclically in (i, j]
+        if (i <= j ? (i < h && h <= j) : (i < h || h <= j))
+            continue;
+        this->ent[i] = f;
+        i = j;
+    }
+    this->ent[i] = Hentry();
+    this->siz--;
+    return true;
+}
+
 void Htable::print() const
 {
     putchar('$');
@@ -133,7 +161,7 @@ void Htable::print() const
 Htable::HtIter Htable::begin()
 {
     // find 1st used entry
-    TIL(i, this->siz) {
+    TIL(i, this->cap) {
         auto e = &this->ent[i];
         if (e->k != nullptr)
             return Htable::HtIter(e);
@@ -143,7 +171,7 @@ Htable::HtIter Htable::begin()
 
 Htable::HtIter Htable::end() const
 {
-    return Htable::HtIter(&this->ent[this->siz]);
+    return Htable::HtIter(&this->ent[this->cap]);
 }
 
 void Htable::next(Htable::HtIter &it) const
@@ -39,6 +39,11 @@ size_t DfIdf::get_len() const
     return this->len;
 }
 
+cbyte_p DfIdf::get_str() const
+{
+    return this->str;
+}
+
 void DfIdf::print() const
 {
     printf("%s", (char *) this->str);
@@ -51,7 +56,12 @@ void DfIdf::eprint() const
 
 bool DfIdf::eq(const char *str) const
 {
-    return bytearr_cmp(this->str, (cbyte_p) str, this->len);
+    return this->eq((cbyte_p) str, std::strlen(str));
+}
+
+bool DfIdf::eq(cbyte_p str, size_t len) const
+{
+    return len == this->len && bytearr_cmp(this->str, str, len);
 }
 
 DfIdf & DfIdf::operator=(DfIdf &&that)
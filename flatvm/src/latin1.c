@@ -18,23 +18,52 @@ int latin1_is_ascii_string(cbyte_p s, size_t len)
 }
 
 void latin1_putchar(uint8_t c)
+{
+    latin1_fputc(c, stdout);
+}
+
+void latin1_print(cbyte_p str, size_t len)
+{
+    latin1_fprint(stdout, str, len);
+}
+
+void latin1_fputc(uint8_t c, FILE *f)
 {
     if (c < 128) {
         // ASCII
-        putchar(c);
+        fputc(c, f);
     } else {
         // convert to 2 byte UTF-8
-        uint8_t b[3] = {
-            192 | (c >> 6), // 110000xx
-            128 | (c & 63), // 10xxxxxx
-            0               // NUL
-        };
-        printf("%s", b);
+        fputc(192 | (c >> 6), f); // 110000xx
+        fputc(128 | (c & 63), f); // 10xxxxxx
     }
 }
 
-void latin1_print(cbyte_p str, size_t len)
+void latin1_fprint(FILE *f, cbyte_p str, size_t len)
 {
     TIL(i, len)
-        latin1_putchar(str[i]);
+        latin1_fputc(str[i], f);
+}
+
+/*
+** þe inverse, `dst` must fit `len` bytes (it can be `src`) & its lengþ is
+** returned by `dst_len`, FALSE if `src` isn't UTF-8 or has chars > U+00FF
+*/
+int latin1_from_utf8(cbyte_p src, size_t len, uint8_t *dst, size_t *dst_len)
+{
+    size_t j = 0;
+    TIL(i, len) {
+        uint8_t c = src[i];
+        if (latin1_is_ascii(c)) {
+            dst[j++] = c;
+            continue;
+        }
+        // must be 1100001x 10xxxxxx, C0 & C1 would be overlong
+        if ((c >> 1) != 0x61 || i + 1 == len || (src[i+1] >> 6) != 2)
+            return FALSE;
+        dst[j++] = (uint8_t) ((c & 3) << 6 | (src[i+1] & 63));
+        i++;
+    }
+    *dst_len = j;
+    return TRUE;
 }
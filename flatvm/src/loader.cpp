@@ -20,6 +20,7 @@ static void load_val_z  (DynArr<DfVal> &, cbyte_p *);
 static void load_val_r  (DynArr<DfVal> &, cbyte_p *);
 static void load_nat_tb (DynArr<DfVal> &, cbyte_p *);
 static void load_array  (DynArr<DfVal> &, cbyte_p *);
+static ObjRef read_array(cbyte_p *);
 
 VmData::VmData(cbyte_p buf, size_t len)
 {
@@ -28,6 +29,10 @@ VmData::VmData(cbyte_p buf, size_t len)
     cbyte_p rp = buf; /* reading pointer */
     if (!check_magic_df(&rp))
         throw std::runtime_error("Magic number not found");
+    uint8_t flags = read_u8(&rp);
+    if (flags & ~DF_FLAG_WRAP)
+        throw std::runtime_error("Unknown header flags");
+    this->wrap = flags & DF_FLAG_WRAP;
     load_idf( this->idf, &rp);
     load_ctn( this->ctn, &rp);
     load_pag(*this,      &rp);
@@ -152,6 +157,10 @@ static void load_nat_tb(DynArr<DfVal> &ctn, cbyte_p *rpp)
       /* mega fall-þru */
       case DF_STD:
       case DF_STD_IO:
+      case DF_STD_A:
+      case DF_STD_M:
+      case DF_STD_S:
+      case DF_STD_T:
       {
         ctn.push(NatFactory::get((NatTblTag) num));
         break;
@@ -162,29 +171,47 @@ static void load_nat_tb(DynArr<DfVal> &ctn, cbyte_p *rpp)
 }
 
 static void load_array(DynArr<DfVal> &ctn, cbyte_p *rpp)
+{
+    ctn.push(DfVal(read_array(rpp)));
+}
+
+// an array of 0x08 has its elements serialized as arrays
+static ObjRef read_array(cbyte_p *rpp)
 {
     uint val_type = read_u8(rpp);
+    DfType typ;
     switch (val_type) {
-      case 0x02: case 0x03: case 0x04: case 0x05:
-        break; // OK
+      case 0x01: typ = DfType::B; break;
+      case 0x02: typ = DfType::C; break;
+      case 0x03: typ = DfType::N; break;
+      case 0x04: typ = DfType::Z; break;
+      case 0x05: typ = DfType::R; break;
+      case 0x08: typ = DfType::A; break;
       default:
         throw std::runtime_error("Constant array of unknown type\n");
     }
     size_t len = read_u16(rpp);
     auto arr_ref = maitre::alloc(OBJ_ARR);
     auto *arr = arr_ref.as_arr();
-    arr->is_nat = false;
-    arr->typ = DfType::V; // init mt
+    new (arr) ArrObj(typ); // typed, even if it's empty
     TIL(i, len) {
         DfVal aux;
         switch (val_type) {
+          case 0x01:
+            switch (read_u8(rpp)) {
+              case 0: aux = DfVal(false); break;
+              case 1: aux = DfVal(true);  break;
+              default: throw std::runtime_error("Bad B% constant\n");
+            }
+            break;
           case 0x02: aux = DfVal(read_u8 (rpp)); break;
           case 0x03: aux = DfVal(read_u32(rpp)); break;
           case 0x04: aux = DfVal(read_i32(rpp)); break;
           case 0x05: aux = DfVal(read_f32(rpp)); break;
+          case 0x08: aux = DfVal(read_array(rpp)); break;
           default: unreachable();
         }
         arr->push(std::move(aux));
     }
-    ctn.push(DfVal(arr_ref));
+    return arr_ref;
 }
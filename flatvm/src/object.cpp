@@ -64,6 +64,28 @@ ArrObj::ArrObj(DfVal &&v)
     this->typ = v.as_type();
 }
 
+ArrObj::ArrObj(DfType ty)
+    : typ(ty)
+{
+    switch (dft2valt(ty)) {
+      case VAL_V: return;
+      case VAL_B:
+        new (&this->as.b) BitArr();
+        break;
+    // oþer cases
+#define BASURA(M, m, t) \
+      case VAL_##M: \
+        new (&this->as.m) DynArr<t>(); \
+        break;
+      BASURA(C, c, uint8_t)
+      BASURA(N, n, uint32_t)
+      BASURA(Z, z, int32_t)
+      BASURA(R, r, float)
+      BASURA(O, o, ObjRef)
+#undef BASURA
+    }
+}
+
 ArrObj::~ArrObj()
 {
     switch (this->typ) {
@@ -194,6 +216,28 @@ AccRes ArrObj::set(uint32_t idx, DfVal &&val)
     return AccRes::OK;
 }
 
+AccRes ArrObj::pop(DfVal &ret)
+{
+    switch (this->typ) {
+      case DfType::V: return AccRes::OUT_OF_BOUNDS;
+      case DfType::B:
+        if (this->as.b.len() == 0)
+            return AccRes::OUT_OF_BOUNDS;
+        ret = DfVal(this->as.b.pop());
+        break;
+    // oþer cases
+#define BASURA(M, x) \
+      case DfType::M:                     \
+        if (this->as.x.len() == 0)        \
+            return AccRes::OUT_OF_BOUNDS; \
+        ret = DfVal(this->as.x.pop());    \
+        break;
+      BASURA_CASES
+#undef BASURA
+    }
+    return AccRes::OK;
+}
+
 AccRes ArrObj::concat(const ArrObj &that, ArrObj &res) const
 {
     if (!this->can_concat(that))
@@ -237,6 +281,10 @@ bool ArrObj::can_concat(const ArrObj &that) const
 void ArrObj::print() const
 {
     putchar('_');
+    if (this->len() == 0) { // maybe typed
+        putchar(';');
+        return;
+    }
     switch (this->typ) {
       case DfType::V: break;
       case DfType::B: {
@@ -258,7 +306,7 @@ void ArrObj::print() const
         break;                          \
       }
       BASURA(C, c, c)
-      BASURA(N, n, u)
+      BASURA(N, n, uu) // 1u, 2u, as in DfVal::print
       BASURA(Z, z, d)
       BASURA(R, r, f)
 #undef BASURA
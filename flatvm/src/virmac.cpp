@@ -3,6 +3,8 @@
 #include <cstdio>
 #include <cstdlib>
 #include <cassert>
+#include <cmath>
+#include <string>
 #include "virmac.h"
 #include "maitre.h"
 #include "object.h"
@@ -17,6 +19,27 @@
 /* static functions */
 void err_cant_op  (const char *, DfVal *);
 void err_dif_types(const char *, DfType, DfType);
+DfVal load_ctn(const DfVal &);
+
+// like Rust's overflowing_pow, so þat boþ engines agree
+template<typename T>
+static bool pow_overflow(T base, uint32_t exp, T *res)
+{
+    T acc = 1;
+    bool ovf = false;
+    if (exp == 0) {
+        *res = 1;
+        return false;
+    }
+    while (exp > 1) {
+        if (exp & 1)
+            ovf |= __builtin_mul_overflow(acc, base, &acc);
+        exp /= 2;
+        ovf |= __builtin_mul_overflow(base, base, &base);
+    }
+    ovf |= __builtin_mul_overflow(acc, base, res);
+    return ovf;
+}
 
 VirMac::VirMac()
 {
@@ -44,7 +67,7 @@ ItpRes VirMac::run(VmData *prog)
     this->dat = prog;
     /* start main */
     this->push_call(&this->stack[0], &prog->pag[0]);
-    ItpRes res = this->_run();
+    ItpRes res = this->_run(0);
     if (res != ITP_OK) {
 //        this->print_stack();
         this->print_calls();
@@ -96,7 +119,51 @@ DfVal & VirMac::peek()
     return this->sp[-1];
 }
 
-ItpRes VirMac::_run()
+// calls a # or ! from a native, wiþ `argc` args, & returns when it does;
+// `ret` only set by a #
+ItpRes VirMac::call(const DfVal &cle, const DfVal *argv, size_t argc,
+                    DfVal &ret)
+{
+    DfVal *base = this->sp;
+    this->push(cle);
+    TIL(i, argc)
+        this->push(argv[i]);
+    Norris *nrs;
+    if (cle.is_fun()) {
+        auto *fun = cle.as.o.as_fun();
+        if (fun->is_nat) {
+            int res = fun->as.nat.eval(*this, base + 1, argc, ret);
+            this->sp = base;
+            return res ? ITP_OK : ITP_RUNTIME_ERR;
+        }
+        nrs = fun->as.usr.nrs;
+    } else if (cle.is_pro()) {
+        auto *pro = cle.as.o.as_pro();
+        if (pro->is_nat) {
+            int res = pro->as.nat.exec(*this, base + 1, argc);
+            this->sp = base;
+            return res ? ITP_OK : ITP_RUNTIME_ERR;
+        }
+        nrs = pro->as.usr.nrs;
+    } else {
+        eputln("cannot call a not # nor !");
+        return ITP_RUNTIME_ERR;
+    }
+    if (nrs->ari != argc) {
+        printf("ERROR: wrong arity calling ");
+        cle.print();
+        puts("");
+        return ITP_RUNTIME_ERR;
+    }
+    this->push_call(base, nrs);
+    ItpRes res = this->_run(this->callnum);
+    if (res == ITP_OK && cle.is_fun())
+        ret = this->pop(); // RET left it at base
+    return res;
+}
+
+// returns when þe call at `base` does (only natives' calls do)
+ItpRes VirMac::_run(int base)
 {
     LOOP {
         uint8_t ins;
@@ -174,6 +241,22 @@ void VirMac::set_norris(Norris *n)
     this->ip = n->cod;
 }
 
+/*
+** an array constant is copied when loaded, so þat each evaluation of a
+** literal gets a new array, like þe ones from AMN & APE
+*/
+DfVal load_ctn(const DfVal &k)
+{
+    if (!k.is_arr())
+        return k;
+    auto a = maitre::alloc(OBJ_ARR);
+    auto arr = a.as_arr();
+    arr->typ = DfType::V;
+    arr->is_nat = false;
+    (void) arr->extend(*k.as.o.as_arr());
+    return DfVal(a);
+}
+
 /* error message for same type but invalid operations */
 void err_cant_op(const char *op, DfVal *v)
 {
@@ -187,6 +270,139 @@ void err_dif_types(const char *op, DfType t1, DfType t2)
         op, (char)t1, (char)t2);
 }
 
+/*
+** þe cast table from doc/values.md, InFarter's Val::cast does þe same
+** & gives þe same error messages; casts `v` in place & returns nullptr if
+** ok, or þe error message
+*/
+static const char * parse_num(DfVal &, DfType);
+
+const char * cast_val(DfVal &v, DfType to)
+{
+    static char msg[64];
+    DfType from = v.as_type();
+    if (from == to)
+        return nullptr;
+    switch (to) {
+      case DfType::C:
+        if (VAL_N != v.type)
+            break;
+        if (v.as.n > UINT8_MAX)
+            goto range;
+        v = DfVal((uint8_t) v.as.n);
+        return nullptr;
+      case DfType::N:
+        switch (v.type) {
+          case VAL_B: v = DfVal((uint32_t) v.as.b); return nullptr;
+          case VAL_C: v = DfVal((uint32_t) v.as.c); return nullptr;
+          case VAL_Z:
+            if (v.as.z < 0)
+                goto range;
+            v = DfVal((uint32_t) v.as.z);
+            return nullptr;
+          case VAL_R: // truncated
+            if (!(v.as.r > -1.0f && v.as.r < 4294967296.0f))
+                goto range;
+            v = DfVal((uint32_t) v.as.r);
+            return nullptr;
+          default:
+            break;
+        }
+        break;
+      case DfType::Z:
+        switch (v.type) {
+          case VAL_N:
+            if (v.as.n > (uint32_t) INT32_MAX)
+                goto range;
+            v = DfVal((int32_t) v.as.n);
+            return nullptr;
+          case VAL_R: // truncated
+            if (!(v.as.r >= -2147483648.0f && v.as.r < 2147483648.0f))
+                goto range;
+            v = DfVal((int32_t) v.as.r);
+            return nullptr;
+          default:
+            break;
+        }
+        break;
+      case DfType::R:
+        switch (v.type) {
+          case VAL_N: v = DfVal((float) v.as.n); return nullptr;
+          case VAL_Z: v = DfVal((float) v.as.z); return nullptr;
+          default: break;
+        }
+        break;
+      default: // B% only from B%
+        break;
+    }
+    if (v.is_arr() && (DfType::N == to || DfType::Z == to || DfType::R == to))
+        return parse_num(v, to);
+    snprintf(msg, sizeof msg, "cannot cast %c%% to %c%%",
+        (char) from, (char) to);
+    return msg;
+  range:
+    snprintf(msg, sizeof msg, "%c%% value out of %c%% range",
+        (char) from, (char) to);
+    return msg;
+}
+
+/* N% `\d+`, Z% `-?\d+` & R% `-?\d+(\.\d+)?`, þe string must be all of it */
+static const char * parse_num(DfVal &v, DfType to)
+{
+    static char msg[64];
+    const ArrObj *a = v.as.o.as_arr();
+    if (DfType::V != a->typ && DfType::C != a->typ) {
+        snprintf(msg, sizeof msg, "cannot cast _%% to %c%%", (char) to);
+        return msg;
+    }
+    std::string s;
+    for (uint32_t i = 0; i < a->len(); ++i) {
+        DfVal c;
+        a->get(i, c);
+        s.push_back((char) c.as.c);
+    }
+    // check þe syntax
+    size_t i = (DfType::N != to && s[0] == '-') ? 1 : 0;
+    size_t start = i;
+    while (i < s.size() && isdigit((unsigned char) s[i]))
+        ++i;
+    bool ok = i > start;
+    if (ok && DfType::R == to && i < s.size() && s[i] == '.') {
+        size_t frac = ++i;
+        while (i < s.size() && isdigit((unsigned char) s[i]))
+            ++i;
+        ok = i > frac;
+    }
+    if (!ok || i != s.size()) {
+        snprintf(msg, sizeof msg, "cannot parse string as %c%%", (char) to);
+        return msg;
+    }
+    // convert
+    if (DfType::R == to) {
+        float r = strtof(s.c_str(), nullptr);
+        if (!std::isinf(r)) {
+            v = DfVal(r);
+            return nullptr;
+        }
+    } else {
+        bool neg = s[0] == '-';
+        uint64_t max = DfType::N == to ? UINT32_MAX
+                     : neg ? (uint64_t) INT32_MAX + 1 : INT32_MAX;
+        uint64_t x = 0;
+        for (size_t j = start; j < s.size() && x <= max; ++j)
+            x = 10 * x + (s[j] - '0');
+        if (x <= max) {
+            if (DfType::N == to)
+                v = DfVal((uint32_t) x);
+            else
+                v = DfVal((int32_t) (neg ? -(int64_t) x : (int64_t) x));
+            return nullptr;
+        }
+    }
+    snprintf(msg, sizeof msg, "string value out of %c%% range", (char) to);
+    return msg;
+}
+
 #define VM_JX_IF(x, read_size, adv_size) \
 void VirMac::j ## x ## _if(bool cond) \
 {                                                   \
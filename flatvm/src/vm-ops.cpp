@@ -58,13 +58,13 @@ OP_LRX(1)
 
 case OP_LKS: {
     uint idx = READ_U8();
-    this->push(DfVal(this->dat->ctn[idx]));
+    this->push(load_ctn(this->dat->ctn[idx]));
     break;
 }
 
 case OP_LKL: {
     uint idx = READ_U16();
-    this->push(this->dat->ctn[idx]);
+    this->push(load_ctn(this->dat->ctn[idx]));
     break;
 }
 
@@ -73,7 +73,13 @@ case OP_LKL: {
 case OP_NEG: {
     DfVal val = this->pop(); // fastest (checked with peek)
     switch (val.type) {
-      case VAL_Z: this->fpush(DfVal(-val.as.z)); break;
+      case VAL_Z: {
+        int32_t res;
+        if (__builtin_sub_overflow(0, val.as.z, &res) && !this->dat->wrap)
+            SIMPLE_ERR("Z% overflow");
+        this->fpush(DfVal(res));
+        break;
+      }
       case VAL_R: this->fpush(DfVal(-val.as.r)); break;
       default: ERR_OP_TYPE("unary -", &val);
     }
@@ -105,6 +111,15 @@ case OP_NEG: {
 
 #define DO_BINOP(x, op) this->push(DfVal(lhs.as.x op rhs.as.x)); break
 
+// N% & Z% ariþmetic is checked, unless compiled wiþ --wrap
+#define INT_BINOP(x, T, bop, msg) do {                          \
+    T res;                                                      \
+    if (__builtin_ ## bop ## _overflow(lhs.as.x, rhs.as.x, &res) \
+    && !this->dat->wrap)                                        \
+        SIMPLE_ERR(msg);                                        \
+    this->push(DfVal(res));                                     \
+} while (false); break
+
 case OP_ADD: {
     DfVal rhs = this->pop();
     DfVal lhs = this->pop();
@@ -112,8 +127,8 @@ case OP_ADD: {
         ERR_BINOP("+");
     switch (lhs.type) {
       case VAL_C: DO_BINOP(c, +);
-      case VAL_N: DO_BINOP(n, +);
-      case VAL_Z: DO_BINOP(z, +);
+      case VAL_N: INT_BINOP(n, uint32_t, add, "N% overflow");
+      case VAL_Z: INT_BINOP(z, int32_t,  add, "Z% overflow");
       case VAL_R: DO_BINOP(r, +);
       case VAL_O: ADD_O; break;
       default: ERR_OP_TYPE("+", &lhs);
@@ -129,7 +144,8 @@ case OP_SUB: {
     if (lhs.type != rhs.type)
         ERR_BINOP("-");
     switch (lhs.type) {
-      case VAL_Z: DO_BINOP(z, -);
+      case VAL_N: INT_BINOP(n, uint32_t, sub, "N% underflow");
+      case VAL_Z: INT_BINOP(z, int32_t,  sub, "Z% overflow");
       case VAL_R: DO_BINOP(r, -);
       default: ERR_OP_TYPE("-", &lhs);
     }
@@ -143,8 +159,8 @@ case OP_MUL: {
         ERR_BINOP("*");
     switch (lhs.type) {
       case VAL_C: DO_BINOP(c, *);
-      case VAL_N: DO_BINOP(n, *);
-      case VAL_Z: DO_BINOP(z, *);
+      case VAL_N: INT_BINOP(n, uint32_t, mul, "N% overflow");
+      case VAL_Z: INT_BINOP(z, int32_t,  mul, "Z% overflow");
       case VAL_R: DO_BINOP(r, *);
       default: ERR_OP_TYPE("*", &lhs);
     }
@@ -156,9 +172,26 @@ case OP_DIV: { // fastest: checked with {pop, peek, ->} & {2pop, fpush}
     DfVal lhs = this->pop();
     if (lhs.type != rhs.type)
         ERR_BINOP("/");
+    if ((lhs.type == VAL_N && rhs.as.n == 0)
+    ||  (lhs.type == VAL_Z && rhs.as.z == 0))
+        SIMPLE_ERR("division by zero");
     switch (lhs.type) {
-//      case VAL_N: this->push(DfVal(lhs.as.n * rhs.as.n)); break;
-//      case VAL_Z: this->push(DfVal(lhs.as.z * rhs.as.z)); break;
+      case VAL_N: DO_BINOP(n, /);
+      case VAL_Z: { // euclidean, so þat þe remainder is never negative
+        int32_t a = lhs.as.z;
+        int32_t b = rhs.as.z;
+        if (a == INT32_MIN && b == -1) {
+            if (!this->dat->wrap)
+                SIMPLE_ERR("Z% overflow");
+            this->push(DfVal(a));
+            break;
+        }
+        int32_t q = a / b;
+        if (a % b < 0)
+            q += b > 0 ? -1 : 1;
+        this->push(DfVal(q));
+        break;
+      }
       case VAL_R: DO_BINOP(r, /);
       default: ERR_OP_TYPE("/", &lhs);
     }
@@ -176,11 +209,16 @@ case OP_INV: {
 
 case OP_INC: {
     DfVal &val = this->peek();
+    bool ovf = false;
     switch (val.type) {
-        case VAL_N: val.as.n += 1; break;
-        case VAL_Z: val.as.z += 1; break;
+        case VAL_N: ovf = __builtin_add_overflow(val.as.n, 1, &val.as.n);
+                    break;
+        case VAL_Z: ovf = __builtin_add_overflow(val.as.z, 1, &val.as.z);
+                    break;
         default: ERR_OP_TYPE("1 +", &val);
     }
+    if (ovf && !this->dat->wrap)
+        SIMPLE_ERR(val.type == VAL_N ? "N% overflow" : "Z% overflow");
     break;
 }
 
@@ -188,7 +226,8 @@ case OP_DEC: {
     DfVal &val = this->peek();
     if (val.type != VAL_Z)
         ERR_OP_TYPE("- 1", &val);
-    val.as.z -= 1;
+    if (__builtin_sub_overflow(val.as.z, 1, &val.as.z) && !this->dat->wrap)
+        SIMPLE_ERR("Z% overflow");
     break;
 }
 
@@ -197,10 +236,12 @@ case OP_MOD: {
     DfVal lhs = this->pop();
     if (rhs.type != VAL_N)
         ERR_BINOP("\\");
+    if (rhs.as.n == 0 && (lhs.type == VAL_N || lhs.type == VAL_Z))
+        SIMPLE_ERR("modulo by zero");
     switch (lhs.type) {
       case VAL_N: DO_BINOP(n, %);
       case VAL_Z: {
-        auto rn = rhs.as.n;
+        int64_t rn = rhs.as.n;
         auto res = ((lhs.as.z % rn) + rn) % rn;
         this->push(DfVal((uint32_t) res));
         break;
@@ -210,6 +251,35 @@ case OP_MOD: {
     break;
 }
 
+case OP_POW: {
+    DfVal rhs = this->pop();
+    DfVal lhs = this->pop();
+    if (lhs.type == VAL_R && rhs.type == VAL_R) {
+        this->push(DfVal(powf(lhs.as.r, rhs.as.r)));
+        break;
+    }
+    if (rhs.type != VAL_N)
+        ERR_BINOP("*^");
+    switch (lhs.type) {
+      case VAL_N: {
+        uint32_t res;
+        if (pow_overflow(lhs.as.n, rhs.as.n, &res) && !this->dat->wrap)
+            SIMPLE_ERR("N% overflow");
+        this->push(DfVal(res));
+        break;
+      }
+      case VAL_Z: {
+        int32_t res;
+        if (pow_overflow(lhs.as.z, rhs.as.n, &res) && !this->dat->wrap)
+            SIMPLE_ERR("Z% overflow");
+        this->push(DfVal(res));
+        break;
+      }
+      default: ERR_BINOP("*^");
+    }
+    break;
+}
+
 // bool/bits ----------------------
 
 case OP_NOT: {
@@ -218,13 +288,13 @@ case OP_NOT: {
       case VAL_B: val.as.b = !val.as.b; break;
       case VAL_C: val.as.c = ~val.as.c; break;
       case VAL_N: val.as.n = ~val.as.n; break;
+      case VAL_Z: val.as.z = ~val.as.z; break;
       default: ERR_OP_TYPE("unary ~", &val);
     }
-    val.as.b = !val.as.b;
     break;
 }
 
-// applicable to B%, C% and N%
+// applicable to B%, C%, N% and Z%
 #define BIT_BINOP(name, b_op, n_op, msg) \
 case OP_ ## name: {             \
     DfVal rhs = this->pop();    \
@@ -235,6 +305,7 @@ case OP_ ## name: {             \
       case VAL_B: DO_BINOP(b, b_op);    \
       case VAL_C: DO_BINOP(c, n_op);    \
       case VAL_N: DO_BINOP(n, n_op);    \
+      case VAL_Z: DO_BINOP(z, n_op);    \
       default: ERR_OP_TYPE(msg, &lhs);  \
     }                           \
     break;                      \
@@ -246,6 +317,39 @@ BIT_BINOP(XOR,  ^, ^, "^")
 
 #undef BIT_BINOP
 
+// þe shift amount is N%, bits shifted out are lost & >> on Z% keeps þe sign
+case OP_SHL: {
+    DfVal rhs = this->pop();
+    DfVal lhs = this->pop();
+    if (rhs.type != VAL_N)
+        ERR_BINOP("<<");
+    uint32_t s = rhs.as.n;
+    switch (lhs.type) {
+      case VAL_N: this->push(DfVal(s < 32 ? lhs.as.n << s : 0u)); break;
+      case VAL_Z: {
+        uint32_t u = s < 32 ? (uint32_t) lhs.as.z << s : 0u;
+        this->push(DfVal((int32_t) u));
+        break;
+      }
+      default: ERR_BINOP("<<");
+    }
+    break;
+}
+
+case OP_SHR: {
+    DfVal rhs = this->pop();
+    DfVal lhs = this->pop();
+    if (rhs.type != VAL_N)
+        ERR_BINOP(">>");
+    uint32_t s = rhs.as.n;
+    switch (lhs.type) {
+      case VAL_N: this->push(DfVal(s < 32 ? lhs.as.n >> s : 0u)); break;
+      case VAL_Z: this->push(DfVal(lhs.as.z >> (s < 31 ? s : 31))); break;
+      default: ERR_BINOP(">>");
+    }
+    break;
+}
+
 // compare ----------------------
 
 #define OP_CEX(XX, cmpop) \
@@ -658,58 +762,36 @@ case OP_LUV: {
 
 // casts ---------------------------
 
-case OP_CAN: {
-    DfVal &val = this->peek();
-    switch (val.type) {
-      case VAL_N: break; // do noþing
-      case VAL_Z:
-        if (val.as.z < 0) // even in unsafe version
-            SIMPLE_ERR("ERROR: casting negative Z% to N%");
-        val = DfVal((uint32_t) val.as.z);
-        break;
-      default:
-        SIMPLE_ERR("err cast N%");
-    }
-    break;
+#define OP_CAX(X) \
+case OP_CA##X: {                                            \
+    const char *err = cast_val(this->peek(), DfType::X);    \
+    if (err != nullptr)                                     \
+        SIMPLE_ERR(err);                                    \
+    break;                                                  \
 }
 
-case OP_CAZ: {
-    DfVal &val = this->peek();
-    switch (val.type) {
-      case VAL_N:
-#ifdef SAFE
-        if (val.as.n > (uint32_t) INT32_MAX)
-            SIMPLE_ERR("ERROR: Overflow casting to Z");
-#endif
-        val = DfVal((int32_t) val.as.n); break;
-      case VAL_Z: break; // do noþing
-      default: SIMPLE_ERR("err cast Z%");
-    }
-    break;
-}
+OP_CAX(B)
+OP_CAX(C)
+OP_CAX(N)
+OP_CAX(Z)
+OP_CAX(R)
 
-case OP_CAR: {
-    DfVal &val = this->peek();
-    switch (val.type) {
-      case VAL_N: val.as.r = (float) val.as.n; break;
-      case VAL_Z: val.as.r = (float) val.as.z; break;
-      case VAL_R: break; // do noþing
-      default: SIMPLE_ERR("err cast R%");
-    }
-    val.type = VAL_R;
-    break;
-}
+#undef OP_CAX
 
 // stack stuff -----------------------
 
 case OP_END:
     this->pop_call();
+    if (this->callnum < base) // back to a native
+        return ITP_OK;
     break;
 
 case OP_RET: {
     DfVal ret = this->pop();
     this->pop_call();
     this->push(std::move(ret));
+    if (this->callnum < base) // back to a native
+        return ITP_OK;
     break;
 }
 
@@ -76,6 +76,11 @@ pub enum Array
     N(Vec<u32>),
     Z(Vec<i32>),
     R(Vec<f32>),
+    // of objects, also homogeneous: all arrays, all tables, etc.
+    A(Vec<MutRc<Array>>),
+    T(Vec<Table>),
+    F(Vec<Func>),
+    P(Vec<Proc>),
 }
 
 impl Array
@@ -88,7 +93,11 @@ impl Array
             Val::N(n) => Self::N(vec![*n]),
             Val::Z(z) => Self::Z(vec![*z]),
             Val::R(r) => Self::R(vec![*r]),
-            _ => panic!("cannot create array from {v}"),
+            Val::A(a) => Self::A(vec![a.clone()]),
+            Val::T(t) => Self::T(vec![t.clone()]),
+            Val::F(f) => Self::F(vec![f.clone()]),
+            Val::P(p) => Self::P(vec![p.clone()]),
+            Val::V => panic!("cannot create array from {v}"),
         }
     }
 
@@ -108,19 +117,30 @@ impl Array
             Type::N => Self::N(Vec::<u32> ::with_capacity(c)),
             Type::Z => Self::Z(Vec::<i32> ::with_capacity(c)),
             Type::R => Self::R(Vec::<f32> ::with_capacity(c)),
-            _ => todo!(),
+            Type::A => Self::A(Vec::with_capacity(c)),
+            Type::T => Self::T(Vec::with_capacity(c)),
+            Type::F => Self::F(Vec::with_capacity(c)),
+            Type::P => Self::P(Vec::with_capacity(c)),
+            Type::V => panic!("cannot create V% array"),
         }
     }
 
     pub fn try_push(&mut self, v: &Val) -> Result<(), String>
     {
         match (&mut *self, v) {
+            (_, Val::V) => return util::format_err!(
+                "cannot push V% value into an array"
+            ),
             (Self::E, _) => *self = Self::singleton(v),
             (Self::B(a), Val::B(b)) => a.push(*b),
             (Self::C(a), Val::C(c)) => a.push(*c),
             (Self::N(a), Val::N(n)) => a.push(*n),
             (Self::Z(a), Val::Z(z)) => a.push(*z),
             (Self::R(a), Val::R(r)) => a.push(*r),
+            (Self::A(a), Val::A(x)) => a.push(x.clone()),
+            (Self::T(a), Val::T(t)) => a.push(t.clone()),
+            (Self::F(a), Val::F(f)) => a.push(f.clone()),
+            (Self::P(a), Val::P(p)) => a.push(p.clone()),
             _ => return util::format_err!(
                 "cannot push {} value into {} array",
                 Type::from(v), self.get_type().unwrap()
@@ -138,6 +158,10 @@ impl Array
             Self::N(_) => Some(Type::N),
             Self::Z(_) => Some(Type::Z),
             Self::R(_) => Some(Type::R),
+            Self::A(_) => Some(Type::A),
+            Self::T(_) => Some(Type::T),
+            Self::F(_) => Some(Type::F),
+            Self::P(_) => Some(Type::P),
         }
     }
 
@@ -153,6 +177,10 @@ impl Array
             Self::N(a) => Val::N(a[i]),
             Self::Z(a) => Val::Z(a[i]),
             Self::R(a) => Val::R(a[i]),
+            Self::A(a) => Val::A(a[i].clone()),
+            Self::T(a) => Val::T(a[i].clone()),
+            Self::F(a) => Val::F(a[i].clone()),
+            Self::P(a) => Val::P(a[i].clone()),
         })
     }
 
@@ -170,14 +198,35 @@ impl Array
             (Self::N(a), Val::N(n)) => a[i] = *n,
             (Self::Z(a), Val::Z(z)) => a[i] = *z,
             (Self::R(a), Val::R(r)) => a[i] = *r,
+            (Self::A(a), Val::A(x)) => a[i] = x.clone(),
+            (Self::T(a), Val::T(t)) => a[i] = t.clone(),
+            (Self::F(a), Val::F(f)) => a[i] = f.clone(),
+            (Self::P(a), Val::P(p)) => a[i] = p.clone(),
             _ => return util::format_err!(
-                "{:?} is not of array's type {:?}",
-                v, self.get_type().unwrap()
+                "cannot set {} value into {} array",
+                Type::from(&v), self.get_type().unwrap()
             )
         }
         Ok(())
     }
 
+    // þe last one, keeping þe type
+    pub fn pop(&mut self) -> Option<Val>
+    {
+        match self {
+            Self::E => None,
+            Self::B(a) => a.pop().map(Val::B),
+            Self::C(a) => a.pop().map(Val::C),
+            Self::N(a) => a.pop().map(Val::N),
+            Self::Z(a) => a.pop().map(Val::Z),
+            Self::R(a) => a.pop().map(Val::R),
+            Self::A(a) => a.pop().map(Val::A),
+            Self::T(a) => a.pop().map(Val::T),
+            Self::F(a) => a.pop().map(Val::F),
+            Self::P(a) => a.pop().map(Val::P),
+        }
+    }
+
     #[inline]
     pub fn len(&self) -> usize
     {
@@ -188,6 +237,10 @@ impl Array
             Self::N(a) => a.len(),
             Self::Z(a) => a.len(),
             Self::R(a) => a.len(),
+            Self::A(a) => a.len(),
+            Self::T(a) => a.len(),
+            Self::F(a) => a.len(),
+            Self::P(a) => a.len(),
         }
     }
 
@@ -205,9 +258,43 @@ impl Array
         }
     }
 
+    // casting a string to N%, Z% or R%, þe string must be all of it:
+    // N% `\d+`, Z% `-?\d+` & R% `-?\d+(\.\d+)?`
+    pub fn parse_num(&self, to: Type) -> Result<Val, String>
+    {
+        let s: &[u8] = match self {
+            Self::C(s) => s,
+            Self::E => b"",
+            _ => return util::format_err!("cannot cast _% to {to}"),
+        };
+        let digits = |d: &[u8]|
+            !d.is_empty() && d.iter().all(u8::is_ascii_digit);
+        let mag = s.strip_prefix(b"-").unwrap_or(s);
+        let ok = match to {
+            Type::N => digits(s),
+            Type::Z => digits(mag),
+            _ => match mag.iter().position(|c| *c == b'.') {
+                Some(i) => digits(&mag[..i]) && digits(&mag[i+1..]),
+                None => digits(mag),
+            },
+        };
+        if !ok {
+            return util::format_err!("cannot parse string as {to}");
+        }
+        // only ASCII by now
+        let s = std::str::from_utf8(s).unwrap();
+        let res = match to {
+            Type::N => s.parse().ok().map(Val::N),
+            Type::Z => s.parse().ok().map(Val::Z),
+            _ => s.parse().ok().filter(|r: &f32| r.is_finite()).map(Val::R),
+        };
+        return res.ok_or_else(|| format!("string value out of {to} range"));
+    }
+
+    // a typed empty array only concatenates wiþ þe same type (or E)
     pub fn add(&self, other: &Self) -> Result<Self, String>
     {
-        if self.is_empty() {
+        if let Self::E = self {
             return Ok(other.clone());
         }
         let mut res = self.clone();
@@ -246,7 +333,7 @@ impl std::convert::TryFrom<&[u8]> for Array
     // s is already stript, ie it has no " arround
     fn try_from(s: &[u8]) -> Result<Self, Self::Error>
     {
-        let mut res = Self::default();
+        let mut res = Self::C(vec![]); // '' is C% too
         let mut i = 0;
         while i < s.len() {
             if s[i] == ESC_CH {
@@ -275,15 +362,28 @@ impl fmt::Display for Array
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
-        // special case for strings (C)
+        // special case for strings (C), only at þe top
         if let Self::C(a) = self {
             return write!(f, "{}", DfStr::from(&a.as_ref()));
         }
+        return self.fmt_elems(f);
+    }
+}
+
+impl Array
+{
+    // nested strings are _a, b; too, as in þe VM, so þey're unambiguous
+    fn fmt_elems(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
         write!(f, "_")?;
         // 1st elem
-        match self.get(0) {
-            None => return write!(f, ";"),
-            Some(v) => write!(f, "{v}")?,
+        match self {
+            Self::E => return write!(f, ";"),
+            Self::A(a) if !a.is_empty() => a[0].borrow().fmt_elems(f)?,
+            _ => match self.get(0) {
+                None => return write!(f, ";"),
+                Some(v) => write!(f, "{v}")?,
+            },
         }
         // now all þe oþers
         match self {
@@ -291,10 +391,21 @@ impl fmt::Display for Array
             Self::B(a) => for b in &a[1..] {
                 write!(f, ", {}", if *b {'T'} else {'F'})?;
             },
-            Self::C(_) => unreachable!(),
-            Self::N(a) => for n in &a[1..] { write!(f, ", {n}")?; },
+            Self::C(a) => for c in &a[1..] {
+                write!(f, ", {}", char::from(*c))?;
+            },
+            Self::N(a) => for n in &a[1..] { write!(f, ", {n}u")?; },
             Self::Z(a) => for z in &a[1..] { write!(f, ", {z}")?; },
             Self::R(a) => for r in &a[1..] { write!(f, ", {r}")?; },
+            Self::A(a) => for x in &a[1..] {
+                write!(f, ", ")?;
+                x.borrow().fmt_elems(f)?;
+            },
+            Self::T(_) |
+            Self::F(_) |
+            Self::P(_) => for i in 1..self.len() {
+                write!(f, ", {}", self.get(i).unwrap())?;
+            },
         }
         write!(f, ";")?;
         return Ok(());
@@ -327,20 +438,22 @@ impl Table
         }
     }
 
-    pub fn set(&mut self, k: &Rc<DfStr>, v: Val)
+    // returns false if it's native (can't change), like del
+    pub fn set(&mut self, k: &Rc<DfStr>, v: Val) -> bool
     {
         match &mut *self {
-            Self::Nat(_) => unreachable!("cannot set a native table yet"),
+            Self::Nat(_) => return false,
             Self::Usr(u) => {
                 for p in &mut u.borrow_mut().iter_mut() {
                     if &p.0 == k {
                         p.1 = v;
-                        return;
+                        return true;
                     }
                 }
                 u.borrow_mut().push((k.clone(), v));
+                return true;
             },
-        };
+        }
     }
 
     pub fn has(&self, k: &DfStr) -> bool
@@ -360,6 +473,40 @@ impl Table
             },
         }
     }
+
+    // in order of insertion, or as þeir lib has þem for natives
+    pub fn keys(&self) -> Vec<Rc<DfStr>>
+    {
+        match &self {
+            Self::Nat(n) => n.keys()
+                .iter()
+                .map(|k| Rc::new(DfStr::from(k.as_bytes())))
+                .collect(),
+            Self::Usr(u) => u.borrow().iter().map(|p| p.0.clone()).collect(),
+        }
+    }
+
+    pub fn size(&self) -> usize
+    {
+        match &self {
+            Self::Nat(n) => n.keys().len(),
+            Self::Usr(u) => u.borrow().len(),
+        }
+    }
+
+    // returns false if k wasn't þere, or if it's native (can't change)
+    pub fn del(&mut self, k: &DfStr) -> bool
+    {
+        match &mut *self {
+            Self::Nat(_) => return false,
+            Self::Usr(u) => {
+                let mut u = u.borrow_mut();
+                let len = u.len();
+                u.retain(|p| &*p.0 != k);
+                return u.len() != len;
+            },
+        }
+    }
 }
 
 impl PartialEq for Table
@@ -393,7 +540,8 @@ impl fmt::Display for Table
     }
 }
 
-// used for procs & funcs
+// used for procs & funcs: þe upvals Rc is new on each evaluation of þe
+// definition, so it's þe identity of þe object (as FMN/PMN in þe VM)
 macro_rules! impl_eq_nat_usr {
     ($tname:ident) => {
 
@@ -401,7 +549,7 @@ impl PartialEq for $tname {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Nat(n), Self::Nat(m)) => n == m,
-            (Self::Usr(u, _), Self::Usr(v, _)) => Rc::ptr_eq(u, v),
+            (Self::Usr(_, s), Self::Usr(_, t)) => Rc::ptr_eq(s, t),
             _ => false,
         }
     }
@@ -411,7 +559,7 @@ impl Eq for $tname {}
     };
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SubrMeta
 {
     pub line: usize, // line where it started (# xor !)
@@ -439,7 +587,7 @@ impl Subr
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum SubrType { F, P }
 
-pub type UpVals = Option<Rc<Vec<Val>>>;
+pub type UpVals = Rc<Vec<Val>>;
 
 #[derive(Debug, Clone)]
 pub enum Proc {
@@ -477,6 +625,8 @@ impl Func
     }
 }
 
+impl_eq_nat_usr!(Func);
+
 #[derive(Debug, Clone)]
 pub enum Val
 {
@@ -533,6 +683,125 @@ impl Val
     {
         Self::P(Proc::Nat(np))
     }
+
+    // þe cast table from doc/values.md, FlatVM's CA* ops do þe same
+    pub fn cast(&self, to: Type) -> Result<Self, String>
+    {
+        let from = Type::from(self);
+        let range = || util::format_err!("{from} value out of {to} range");
+        let res = match (self, to) {
+            _ if from == to => self.clone(),
+            (Val::B(b), Type::N) => Val::N(u32::from(*b)),
+            (Val::C(c), Type::N) => Val::N(u32::from(*c)),
+            (Val::N(n), Type::C) => match u8::try_from(*n) {
+                Ok(c) => Val::C(c),
+                _ => return range(),
+            },
+            (Val::N(n), Type::Z) => match i32::try_from(*n) {
+                Ok(z) => Val::Z(z),
+                _ => return range(),
+            },
+            (Val::Z(z), Type::N) => match u32::try_from(*z) {
+                Ok(n) => Val::N(n),
+                _ => return range(),
+            },
+            (Val::N(n), Type::R) => Val::R(*n as f32),
+            (Val::Z(z), Type::R) => Val::R(*z as f32),
+            // truncated, like C
+            (Val::R(r), Type::N) => if *r > -1.0 && *r < 4_294_967_296.0 {
+                Val::N(*r as u32)
+            } else {
+                return range();
+            },
+            (Val::R(r), Type::Z) =>
+                if *r >= -2_147_483_648.0 && *r < 2_147_483_648.0 {
+                    Val::Z(*r as i32)
+                } else {
+                    return range();
+                },
+            (Val::A(a), Type::N | Type::Z | Type::R) =>
+                return a.borrow().parse_num(to),
+            _ => return util::format_err!("cannot cast {from} to {to}"),
+        };
+        return Ok(res);
+    }
+
+    // N% & Z% ariþmetic, þe same in tarzan, optimus & FlatVM: overflows
+    // are errors unless `wrap`, division & modulo by zero always are; None
+    // if `o` is not an integer op for þese types
+    pub fn int_arith(&self, o: BinOpcode, r: &Self, wrap: bool)
+     -> Option<Result<Self, String>>
+    {
+        let (res, ovf) = match (self, o, r) {
+            (Val::N(_) | Val::Z(_), BinOpcode::Mod, Val::N(0)) =>
+                return Some(util::format_err!("modulo by zero")),
+            (Val::N(_), BinOpcode::Div, Val::N(0)) |
+            (Val::Z(_), BinOpcode::Div, Val::Z(0)) =>
+                return Some(util::format_err!("division by zero")),
+            (Val::N(vl), BinOpcode::Mod, Val::N(vr)) =>
+                (Val::N(vl % vr), false),
+            (Val::Z(vl), BinOpcode::Mod, Val::N(vr)) => {
+                let rem = i64::from(*vl).rem_euclid(i64::from(*vr));
+                (Val::N(u32::try_from(rem).unwrap()), false)
+            },
+            // bits shifted out are lost, >> on Z% keeps þe sign
+            (Val::N(vl), BinOpcode::Shl, Val::N(vr)) =>
+                (Val::N(vl.checked_shl(*vr).unwrap_or(0)), false),
+            (Val::N(vl), BinOpcode::Shr, Val::N(vr)) =>
+                (Val::N(vl.checked_shr(*vr).unwrap_or(0)), false),
+            (Val::Z(vl), BinOpcode::Shl, Val::N(vr)) =>
+                (Val::Z(vl.checked_shl(*vr).unwrap_or(0)), false),
+            (Val::Z(vl), BinOpcode::Shr, Val::N(vr)) =>
+                (Val::Z(vl >> (*vr).min(31)), false),
+            (Val::N(vl), BinOpcode::Pow, Val::N(vr)) =>
+                wrapped(vl.overflowing_pow(*vr), Val::N),
+            (Val::Z(vl), BinOpcode::Pow, Val::N(vr)) =>
+                wrapped(vl.overflowing_pow(*vr), Val::Z),
+            (Val::N(vl), _, Val::N(vr)) => match o {
+                BinOpcode::Add => wrapped(vl.overflowing_add(*vr), Val::N),
+                BinOpcode::Sub => wrapped(vl.overflowing_sub(*vr), Val::N),
+                BinOpcode::Mul => wrapped(vl.overflowing_mul(*vr), Val::N),
+                BinOpcode::Div => (Val::N(vl / vr), false),
+                _ => return None,
+            },
+            (Val::Z(vl), _, Val::Z(vr)) => match o {
+                BinOpcode::Add => wrapped(vl.overflowing_add(*vr), Val::Z),
+                BinOpcode::Sub => wrapped(vl.overflowing_sub(*vr), Val::Z),
+                BinOpcode::Mul => wrapped(vl.overflowing_mul(*vr), Val::Z),
+                // euclidean, so þat þe remainder is never negative, like \
+                BinOpcode::Div =>
+                    wrapped(vl.overflowing_div_euclid(*vr), Val::Z),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if !ovf || wrap {
+            return Some(Ok(res));
+        }
+        return Some(match (self, o) {
+            (Val::N(_), BinOpcode::Sub) => util::format_err!("N% underflow"),
+            _ => util::format_err!("{} overflow", Type::from(self)),
+        });
+    }
+
+    // unary minus, checked like int_arith
+    pub fn neg(&self, wrap: bool) -> Option<Result<Self, String>>
+    {
+        return match self {
+            Val::Z(z) => match z.overflowing_neg() {
+                (_, true) if !wrap => Some(util::format_err!("Z% overflow")),
+                (n, _) => Some(Ok(Val::Z(n))),
+            },
+            Val::R(r) => Some(Ok(Val::R(-r))),
+            _ => None,
+        };
+    }
+}
+
+// for int_arith
+fn wrapped<T>((x, ovf): (T, bool), f: fn(T) -> Val) -> (Val, bool)
+{
+    return (f(x), ovf);
 }
 
 impl PartialEq for Val
@@ -545,7 +814,7 @@ impl PartialEq for Val
             (Val::C(c), Val::C(d)) => c == d,
             (Val::N(n), Val::N(m)) => n == m,
             (Val::Z(z), Val::Z(a)) => z == a,
-            (Val::F(_), Val::F(_)) => false, // FIXME Rc::ptr_eq(f, g),
+            (Val::F(f), Val::F(g)) => f == g,
             (Val::P(p), Val::P(q)) => p == q,
             (Val::A(a), Val::A(b)) => *a.borrow() == *b.borrow(),
             (Val::T(t), Val::T(r)) => t == r,
@@ -593,9 +862,9 @@ impl fmt::Display for Val
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum BinOpcode {
-    Add, Sub, Mul, Div, Mod,
+    Add, Sub, Mul, Div, Mod, Pow,
     Eq, Ne, Lt, Gt, Le, Ge,
-    And, Or, Xor, Cand, Cor,
+    And, Or, Xor, Shl, Shr, Cand, Cor,
     Idx
 }
 
@@ -608,7 +877,8 @@ impl BinOpcode
             Self::Sub |
             Self::Mul |
             Self::Div |
-            Self::Mod
+            Self::Mod |
+            Self::Pow
         )
     }
 
@@ -617,7 +887,9 @@ impl BinOpcode
         matches!(self,
             Self::And |
             Self::Or  |
-            Self::Xor
+            Self::Xor |
+            Self::Shl |
+            Self::Shr
         )
     }
 
@@ -677,8 +949,60 @@ pub struct SwCase
     pub blok: Block,
 }
 
+// where a node comes from in þe source
+#[derive(Default, Copy, Clone, Eq, PartialEq)]
+pub struct Span
+{
+    pub lo:   usize, // byte offsets, [lo, hi)
+    pub hi:   usize,
+    pub line: usize, // of `lo`, from 1
+    pub col:  usize, // of `lo`, from 1
+}
+
+impl Span
+{
+    // from þe start of `self` to þe end of `other`
+    #[must_use]
+    pub fn to(&self, other: &Span) -> Span
+    {
+        return Span { hi: other.hi.max(self.hi), ..*self };
+    }
+}
+
+impl fmt::Display for Span
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+// so þat --emit=ast stays readable
+impl fmt::Debug for Span
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "@{self}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Stmt
+{
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt
+{
+    pub fn new(k: StmtKind, s: Span) -> Self
+    {
+        return Self { kind: k, span: s };
+    }
+}
+
 #[derive(Debug, Clone)]
-pub enum Stmt
+pub enum StmtKind
 {
     Assign(Expr, Expr),
     OperOn(Expr, BinOpcode, Expr),
@@ -694,7 +1018,22 @@ pub enum Stmt
 }
 
 #[derive(Debug, Clone)]
-pub enum Expr
+pub struct Expr
+{
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr
+{
+    pub fn new(k: ExprKind, s: Span) -> Self
+    {
+        return Self { kind: k, span: s };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind
 {
     Const(Val),
     Ident(Rc<DfStr>),
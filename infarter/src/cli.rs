@@ -0,0 +1,194 @@
+/* cli.rs */
+
+use crate::util::{self, StrRes};
+
+pub const USAGE: &str = "\
+usage: infarter [run] [--wrap] <file.df | ->
+       infarter compile [-o <path>] [-O0 | -O1 | -O2] [--emit=<what>]
+                        [--wrap] <file.df | ->
+       infarter check <file.df | ->
+       infarter dis [--roundtrip] <file.dfc | ->
+       infarter verify <file.dfc | ->
+       infarter repl
+
+  run       execute þe source wiþ þe tree-walk interpreter (default)
+  compile   transfart þe source into FlatVM bytecode
+  check     only parse & analyse þe source, reporting any errors
+  dis       disassemble a compiled .dfc file
+  verify    check þat a .dfc file is well formed before running it
+  repl      interactive interpreter (also wiþout any arguments)
+
+  -          read þe source from stdin
+  -o <path>  write þe output to <path> (`-` for stdout)
+  -O<n>      optimization level: 0 (default), 1 or 2
+  --emit=    stop compiling after some stage & write its output:
+             tokens, ast, ir or bytecode (default)
+  --wrap     (run, compile) N% & Z% overflows wrap around, instead of
+             being runtime errors
+  --roundtrip  (dis) instead, check þat decoding & re-encoding þe .dfc
+             gives back þe same bytes";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cmd
+{
+    Run,
+    Compile,
+    Check,
+    Dis,
+    Verify,
+    Repl,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Emit
+{
+    Tokens,
+    Ast,
+    Ir,
+    #[default]
+    Bytecode,
+}
+
+impl TryFrom<&str> for Emit
+{
+    type Error = String;
+    fn try_from(s: &str) -> Result<Self, Self::Error>
+    {
+        match s {
+            "tokens"   => Ok(Self::Tokens),
+            "ast"      => Ok(Self::Ast),
+            "ir"       => Ok(Self::Ir),
+            "bytecode" => Ok(Self::Bytecode),
+            _ => util::format_err!("unknown --emit mode `{s}`"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Args
+{
+    pub cmd:    Cmd,
+    pub input:  String,         // source paþ, "-" is stdin
+    pub output: Option<String>, // "-" is stdout
+    pub opt:    u8,             // 0, 1 or 2
+    pub emit:   Emit,
+    pub roundtrip: bool,
+    pub wrap:   bool,           // wrapping integer ariþmetic
+}
+
+impl Args
+{
+    // `argv` wiþout þe program name
+    pub fn parse(argv: &[String]) -> StrRes<Self>
+    {
+        let Some(first) = argv.first() else {
+            return Ok(Self::repl());
+        };
+        // þe 3rd is þe default optimization level
+        let (cmd, rest, dft_opt) = match first.as_str() {
+            "run"     => (Cmd::Run,     &argv[1..], 0),
+            "check"   => (Cmd::Check,   &argv[1..], 0),
+            "dis"     => (Cmd::Dis,     &argv[1..], 0),
+            "verify"  => (Cmd::Verify,  &argv[1..], 0),
+            "repl" if argv.len() == 1 => return Ok(Self::repl()),
+            // `t` & `to` are þe old short forms, still used by some scripts
+            "compile" |
+            "t"       => (Cmd::Compile, &argv[1..], 0),
+            "to"      => (Cmd::Compile, &argv[1..], 2),
+            _         => (Cmd::Run,     argv,       0),
+        };
+        return Self::parse_opts(cmd, rest, dft_opt);
+    }
+
+    fn parse_opts(cmd: Cmd, argv: &[String], dft_opt: u8) -> StrRes<Self>
+    {
+        let mut input  = None;
+        let mut output = None;
+        let mut opt    = None;
+        let mut emit   = None;
+        let mut roundtrip = false;
+        let mut wrap = false;
+        let mut iter = argv.iter();
+        while let Some(a) = iter.next() {
+            match a.as_str() {
+                "-o" => {
+                    let Some(p) = iter.next() else {
+                        return util::format_err!("expected a paþ after -o");
+                    };
+                    set_once(&mut output, p.clone(), "-o")?;
+                },
+                "-O0" => set_once(&mut opt, 0, "-O")?,
+                "-O1" => set_once(&mut opt, 1, "-O")?,
+                "-O2" => set_once(&mut opt, 2, "-O")?,
+                "--roundtrip" if cmd == Cmd::Dis => roundtrip = true,
+                "--wrap" if matches!(cmd, Cmd::Run | Cmd::Compile) =>
+                    wrap = true,
+                _ => if let Some(e) = a.strip_prefix("--emit=") {
+                    set_once(&mut emit, Emit::try_from(e)?, "--emit")?;
+                } else if a.starts_with('-') && a != "-" {
+                    return util::format_err!("unknown option `{a}`");
+                } else {
+                    set_once(&mut input, a.clone(), "input file")?;
+                },
+            }
+        }
+        let Some(input) = input else {
+            return util::format_err!("no input file");
+        };
+        if cmd != Cmd::Compile
+        && (output.is_some() || opt.is_some() || emit.is_some()) {
+            return util::format_err!(
+                "-o, -O & --emit are only valid when compiling");
+        }
+        return Ok(Self {
+            cmd:    cmd,
+            input:  input,
+            output: output,
+            opt:    opt.unwrap_or(dft_opt),
+            emit:   emit.unwrap_or_default(),
+            roundtrip: roundtrip,
+            wrap:   wrap,
+        });
+    }
+
+    fn repl() -> Self
+    {
+        Self {
+            cmd:    Cmd::Repl,
+            input:  String::new(),
+            output: None,
+            opt:    0,
+            emit:   Emit::default(),
+            roundtrip: false,
+            wrap:   false,
+        }
+    }
+
+    pub fn from_stdin(&self) -> bool
+    {
+        self.input == "-"
+    }
+
+    // where to write þe compiled output, None is stdout
+    pub fn output_path(&self) -> Option<String>
+    {
+        match &self.output {
+            Some(o) if o == "-" => None,
+            Some(o) => Some(o.clone()),
+            // text is for humans
+            None if self.emit != Emit::Bytecode => None,
+            None if self.from_stdin() => None,
+            None => Some(format!("{}c", self.input)),
+        }
+    }
+}
+
+// helper for parse_opts
+fn set_once<T>(slot: &mut Option<T>, val: T, what: &str) -> StrRes<()>
+{
+    if slot.is_some() {
+        return util::format_err!("{what} given more þan once");
+    }
+    *slot = Some(val);
+    return Ok(());
+}
@@ -1,16 +1,65 @@
 /* dflib/funcs.rs */
 
+use std::{rc::Rc, cell::RefCell, io::{self, BufRead, Read, Write}};
 use crate::{
-    asterix::Val,
-    util::StrRes,
+    asterix::{Val, Table, Array, Type},
+    dflib::Caller,
+    util::{StrRes, DfStr, MutRc},
     util,
 };
 
+// discriminants are FlatVM's NatFunTag in df-lib.h
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum NatFn
 {
-    A_LEN,
+    A_LEN = 0,
+    IO_READFILE,
+    EQ,
+    IO_READLN,
+    IO_READALL,
+    M_SQRT,
+    M_POW,
+    M_EXP,
+    M_LN,
+    M_SIN,
+    M_COS,
+    M_TAN,
+    M_ATAN2,
+    M_FLOOR,
+    M_CEIL,
+    M_ROUND,
+    M_ABS,
+    M_MIN,
+    M_MAX,
+    S_FIND,
+    S_SPLIT,
+    S_JOIN,
+    S_TRIM,
+    S_UPPER,
+    S_LOWER,
+    S_STARTSWITH,
+    S_ENDSWITH,
+    S_REPLACE,
+    S_FROMN,
+    S_FROMZ,
+    S_FROMR,
+    S_TON,
+    S_TOZ,
+    S_TOR,
+    A_LAST,
+    A_SLICE,
+    A_COPY,
+    A_INDEXOF,
+    A_MAP,
+    A_FILTER,
+    A_FOLD,
+    T_KEYS,
+    T_HAS,
+    T_GET,
+    T_LEN,
+    T_COPY,
+    T_MERGE,
 }
 
 impl NatFn
@@ -18,15 +67,110 @@ impl NatFn
     pub fn arity(&self) -> usize
     {
         match self {
-            Self::A_LEN => 1,
+            Self::IO_READLN |
+            Self::IO_READALL => 0,
+            Self::A_LEN |
+            Self::IO_READFILE |
+            Self::M_SQRT |
+            Self::M_EXP |
+            Self::M_LN |
+            Self::M_SIN |
+            Self::M_COS |
+            Self::M_TAN |
+            Self::M_FLOOR |
+            Self::M_CEIL |
+            Self::M_ROUND |
+            Self::M_ABS |
+            Self::S_TRIM |
+            Self::S_UPPER |
+            Self::S_LOWER |
+            Self::S_FROMN |
+            Self::S_FROMZ |
+            Self::S_FROMR |
+            Self::S_TON |
+            Self::S_TOZ |
+            Self::S_TOR |
+            Self::A_LAST |
+            Self::A_COPY |
+            Self::T_KEYS |
+            Self::T_LEN |
+            Self::T_COPY => 1,
+            Self::EQ |
+            Self::M_POW |
+            Self::M_ATAN2 |
+            Self::M_MIN |
+            Self::M_MAX |
+            Self::S_FIND |
+            Self::S_SPLIT |
+            Self::S_JOIN |
+            Self::S_STARTSWITH |
+            Self::S_ENDSWITH |
+            Self::A_INDEXOF |
+            Self::A_MAP |
+            Self::A_FILTER |
+            Self::T_HAS |
+            Self::T_GET |
+            Self::T_MERGE => 2,
+            Self::S_REPLACE |
+            Self::A_SLICE |
+            Self::A_FOLD => 3,
         }
     }
 
-    pub fn eval(&self, args: &[Val]) -> StrRes<Val>
+    // `c` calls back user subroutines, for þe higher-order ones
+    pub fn eval<C: Caller>(&self, args: &[Val], c: &C)
+     -> Result<Val, C::Error>
     {
-        match self {
-            Self::A_LEN => a_len(args),
-        }
+        let res = match self {
+            Self::A_LEN       => a_len(args),
+            Self::IO_READFILE => io_read_file(args),
+            Self::EQ          => std_eq(args),
+            Self::IO_READLN   => io_read_ln(),
+            Self::IO_READALL  => io_read_all(),
+            Self::M_SQRT  => m_r_to_r(args, "sqrt", f32::sqrt),
+            Self::M_POW   => m_rr_to_r(args, "pow", f32::powf),
+            Self::M_EXP   => m_r_to_r(args, "exp", f32::exp),
+            Self::M_LN    => m_r_to_r(args, "ln", f32::ln),
+            Self::M_SIN   => m_r_to_r(args, "sin", f32::sin),
+            Self::M_COS   => m_r_to_r(args, "cos", f32::cos),
+            Self::M_TAN   => m_r_to_r(args, "tan", f32::tan),
+            Self::M_ATAN2 => m_rr_to_r(args, "atan2", f32::atan2),
+            Self::M_FLOOR => m_r_to_z(args, "floor", f32::floor),
+            Self::M_CEIL  => m_r_to_z(args, "ceil", f32::ceil),
+            Self::M_ROUND => m_r_to_z(args, "round", f32::round),
+            Self::M_ABS   => m_abs(args),
+            Self::M_MIN   => m_min_max(args, "min", false),
+            Self::M_MAX   => m_min_max(args, "max", true),
+            Self::S_FIND       => s_find(args),
+            Self::S_SPLIT      => s_split(args),
+            Self::S_JOIN       => s_join(args),
+            Self::S_TRIM       => s_trim(args),
+            Self::S_UPPER      => s_map(args, "upper", latin1_upper),
+            Self::S_LOWER      => s_map(args, "lower", latin1_lower),
+            Self::S_STARTSWITH => s_starts_ends(args, "startsWith", true),
+            Self::S_ENDSWITH   => s_starts_ends(args, "endsWith", false),
+            Self::S_REPLACE    => s_replace(args),
+            Self::S_FROMN      => s_from_num(args, "fromN", Type::N),
+            Self::S_FROMZ      => s_from_num(args, "fromZ", Type::Z),
+            Self::S_FROMR      => s_from_num(args, "fromR", Type::R),
+            Self::S_TON        => s_to_num(args, "toN", Type::N),
+            Self::S_TOZ        => s_to_num(args, "toZ", Type::Z),
+            Self::S_TOR        => s_to_num(args, "toR", Type::R),
+            Self::A_LAST    => a_last(args),
+            Self::A_SLICE   => a_slice(args),
+            Self::A_COPY    => a_copy(args),
+            Self::A_INDEXOF => a_index_of(args),
+            Self::A_MAP     => return a_map(args, c),
+            Self::A_FILTER  => return a_filter(args, c),
+            Self::A_FOLD    => return a_fold(args, c),
+            Self::T_KEYS  => t_keys(args),
+            Self::T_HAS   => t_has(args),
+            Self::T_GET   => t_get(args),
+            Self::T_LEN   => t_len(args),
+            Self::T_COPY  => t_copy(args),
+            Self::T_MERGE => t_merge(args),
+        };
+        return res.map_err(C::Error::from);
     }
 }
 
@@ -37,3 +181,545 @@ pub fn a_len(args: &[Val]) -> StrRes<Val>
         _ => util::format_err!("not rite typ'arg: expected array"),
     }
 }
+
+// UTF-8 text into a C% array, erring if it can't be Latin-1
+fn new_string(buf: Vec<u8>, who: &str) -> StrRes<Val>
+{
+    let s = String::from_utf8(buf).ok()
+        .and_then(|s| DfStr::try_from(s).ok())
+        .ok_or_else(|| format!("{who}: text is not UTF-8 or not Latin-1"))?;
+    return Ok(Val::from_array(Array::C(s.as_bytes().to_vec())));
+}
+
+// þe UTF-8 path from a C% one
+pub fn utf8_path(v: &Val, who: &str) -> StrRes<String>
+{
+    match v {
+        Val::A(a) => match &*a.borrow() {
+            Array::C(s) => Ok(DfStr::from(s.as_slice()).to_string()),
+            _ => util::format_err!("{who}: path is not a C% array"),
+        },
+        _ => util::format_err!("{who}: path is not a C% array"),
+    }
+}
+
+fn io_read_ln() -> StrRes<Val>
+{
+    let _ = io::stdout().flush(); // for prompts
+    let mut buf = vec![];
+    let n = io::stdin().lock().read_until(b'\n', &mut buf)
+        .map_err(|e| format!("STD$io$readLn: {e}"))?;
+    if n == 0 {
+        return Ok(Val::V); // EOF
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+    }
+    if buf.last() == Some(&b'\r') {
+        buf.pop();
+    }
+    return new_string(buf, "STD$io$readLn");
+}
+
+fn io_read_all() -> StrRes<Val>
+{
+    let _ = io::stdout().flush();
+    let mut buf = vec![];
+    let n = io::stdin().lock().read_to_end(&mut buf)
+        .map_err(|e| format!("STD$io$readAll: {e}"))?;
+    if n == 0 {
+        return Ok(Val::V); // EOF
+    }
+    return new_string(buf, "STD$io$readAll");
+}
+
+fn io_read_file(args: &[Val]) -> StrRes<Val>
+{
+    let path = utf8_path(&args[0], "STD$io$readFile")?;
+    let buf = std::fs::read(&path)
+        .map_err(|_| format!("STD$io$readFile: cannot open {path}"))?;
+    return new_string(buf, "STD$io$readFile");
+}
+
+pub fn std_eq(args: &[Val]) -> StrRes<Val>
+{
+    return Ok(Val::B(deep_eq(&args[0], &args[1], &mut vec![])));
+}
+
+// pairs of arrays or tables already being compared
+type Seen = Vec<(*const (), *const ())>;
+
+/*
+**  structural equality: arrays & user tables are equal if þeir elements
+**  (or fields, in any order) are, R% values are compared exactly, & þe rest
+**  as wiþ ==. A pair already being compared is assumed equal, so cycles end.
+*/
+pub fn deep_eq(lhs: &Val, rhs: &Val, seen: &mut Seen) -> bool
+{
+    match (lhs, rhs) {
+        (Val::R(lr), Val::R(rr)) => lr == rr,
+        (Val::A(la), Val::A(ra)) => {
+            if !visit(Rc::as_ptr(la).cast(), Rc::as_ptr(ra).cast(), seen) {
+                return true;
+            }
+            let (la, ra) = (la.borrow(), ra.borrow());
+            return la.get_type() == ra.get_type()
+                && la.len() == ra.len()
+                && (0..la.len()).all(|i| match (la.get(i), ra.get(i)) {
+                    (Some(lv), Some(rv)) => deep_eq(&lv, &rv, seen),
+                    _ => false,
+                });
+        },
+        (Val::T(Table::Usr(lt)), Val::T(Table::Usr(rt))) => {
+            if !visit(Rc::as_ptr(lt).cast(), Rc::as_ptr(rt).cast(), seen) {
+                return true;
+            }
+            let (lt, rt) = (lt.borrow(), rt.borrow());
+            return lt.len() == rt.len()
+                && lt.iter().all(|(k, lv)| rt
+                    .iter()
+                    .find(|p| p.0 == *k)
+                    .is_some_and(|p| deep_eq(lv, &p.1, seen)));
+        },
+        _ => lhs == rhs,
+    }
+}
+
+// false if þe pair was already seen (or it's þe same object)
+fn visit(x: *const (), y: *const (), seen: &mut Seen) -> bool
+{
+    if x == y || seen.contains(&(x, y)) {
+        return false;
+    }
+    seen.push((x, y));
+    return true;
+}
+
+// STD$m, all on R% unless said oþerwise
+
+fn m_real(v: &Val, name: &str) -> StrRes<f32>
+{
+    match v {
+        Val::R(r) => Ok(*r),
+        _ => util::format_err!(
+            "STD$m${name}: expected R% argument, found {}", Type::from(v)),
+    }
+}
+
+fn m_r_to_r(args: &[Val], name: &str, f: fn(f32) -> f32) -> StrRes<Val>
+{
+    let x = m_real(&args[0], name)?;
+    return Ok(Val::R(f(x)));
+}
+
+fn m_rr_to_r(args: &[Val], name: &str, f: fn(f32, f32) -> f32)
+ -> StrRes<Val>
+{
+    let x = m_real(&args[0], name)?;
+    let y = m_real(&args[1], name)?;
+    return Ok(Val::R(f(x, y)));
+}
+
+// rounded R% into a Z%, erring if out of range like þe cast
+fn m_r_to_z(args: &[Val], name: &str, f: fn(f32) -> f32) -> StrRes<Val>
+{
+    let x = m_real(&args[0], name)?;
+    return Val::R(f(x)).cast(Type::Z)
+        .map_err(|e| format!("STD$m${name}: {e}"));
+}
+
+// Z% or R%
+fn m_abs(args: &[Val]) -> StrRes<Val>
+{
+    match &args[0] {
+        Val::Z(z) => z.checked_abs().map(Val::Z).ok_or_else(||
+            format!("STD$m$abs: Z% value {z} has no Z% absolute value")),
+        Val::R(r) => Ok(Val::R(r.abs())),
+        v => util::format_err!(
+            "STD$m$abs: expected Z% or R% argument, found {}", Type::from(v)),
+    }
+}
+
+// boþ N%, Z% or R%
+fn m_min_max(args: &[Val], name: &str, max: bool) -> StrRes<Val>
+{
+    let res = match (&args[0], &args[1]) {
+        (Val::N(x), Val::N(y)) =>
+            Val::N(if max { *x.max(y) } else { *x.min(y) }),
+        (Val::Z(x), Val::Z(y)) =>
+            Val::Z(if max { *x.max(y) } else { *x.min(y) }),
+        (Val::R(x), Val::R(y)) =>
+            Val::R(if max { x.max(*y) } else { x.min(*y) }),
+        (x, y) => return util::format_err!(
+            "STD$m${name}: expected two N%, Z% or R% of þe same type, \
+            found {} and {}", Type::from(x), Type::from(y)),
+    };
+    return Ok(res);
+}
+
+// STD$s, strings are C% arrays (or untyped empty ones)
+
+fn s_str(v: &Val, name: &str) -> StrRes<Vec<u8>>
+{
+    if let Val::A(a) = v {
+        match &*a.borrow() {
+            Array::C(s) => return Ok(s.clone()),
+            Array::E => return Ok(vec![]),
+            _ => {},
+        }
+    }
+    return util::format_err!(
+        "STD$s${name}: expected C% string, found {}", Type::from(v));
+}
+
+fn s_new(s: Vec<u8>) -> Val
+{
+    return Val::from_array(Array::C(s));
+}
+
+// index of þe 1st `sub` in `s` from `i` on
+fn find_from(s: &[u8], sub: &[u8], i: usize) -> Option<usize>
+{
+    if sub.is_empty() {
+        return Some(i);
+    }
+    return s[i..].windows(sub.len()).position(|w| w == sub).map(|j| i + j);
+}
+
+fn is_space(c: u8) -> bool
+{
+    return matches!(c, b' ' | b'\t' | b'\n' | b'\r');
+}
+
+// ß & ÿ have no Latin-1 uppercase
+fn latin1_upper(c: u8) -> u8
+{
+    match c {
+        b'a'..=b'z' | 0xE0..=0xFE if c != 0xF7 => c - 32, // not ÷
+        _ => c,
+    }
+}
+
+fn latin1_lower(c: u8) -> u8
+{
+    match c {
+        b'A'..=b'Z' | 0xC0..=0xDE if c != 0xD7 => c + 32, // not ×
+        _ => c,
+    }
+}
+
+// N% index or V
+fn s_find(args: &[Val]) -> StrRes<Val>
+{
+    let s = s_str(&args[0], "find")?;
+    let sub = s_str(&args[1], "find")?;
+    return Ok(match find_from(&s, &sub, 0) {
+        Some(i) => Val::N(i as u32),
+        None => Val::V,
+    });
+}
+
+// an empty `sep` splits by whitespace, wiþout empty strings
+fn s_split(args: &[Val]) -> StrRes<Val>
+{
+    let s = s_str(&args[0], "split")?;
+    let sep = s_str(&args[1], "split")?;
+    let parts: Vec<&[u8]> = if sep.is_empty() {
+        s.split(|c| is_space(*c)).filter(|w| !w.is_empty()).collect()
+    } else {
+        let mut res = vec![];
+        let mut i = 0;
+        while let Some(j) = find_from(&s, &sep, i) {
+            res.push(&s[i..j]);
+            i = j + sep.len();
+        }
+        res.push(&s[i..]);
+        res
+    };
+    let arr = parts.into_iter()
+        .map(|p| Rc::new(RefCell::new(Array::C(p.to_vec()))))
+        .collect();
+    return Ok(Val::from_array(Array::A(arr)));
+}
+
+fn s_join(args: &[Val]) -> StrRes<Val>
+{
+    let sep = s_str(&args[1], "join")?;
+    let parts = match &args[0] {
+        Val::A(a) => match &*a.borrow() {
+            Array::A(ps) => Some(ps.iter()
+                .map(|p| s_str(&Val::A(p.clone()), "join"))
+                .collect::<StrRes<Vec<_>>>()?),
+            Array::E => Some(vec![]),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(parts) = parts else {
+        return util::format_err!(
+            "STD$s$join: expected array of C% strings, found {}",
+            Type::from(&args[0]));
+    };
+    return Ok(s_new(parts.join(sep.as_slice())));
+}
+
+fn s_trim(args: &[Val]) -> StrRes<Val>
+{
+    let s = s_str(&args[0], "trim")?;
+    let beg = s.iter().position(|c| !is_space(*c)).unwrap_or(s.len());
+    let end = s.iter().rposition(|c| !is_space(*c)).map_or(beg, |i| i + 1);
+    return Ok(s_new(s[beg..end].to_vec()));
+}
+
+fn s_map(args: &[Val], name: &str, f: fn(u8) -> u8) -> StrRes<Val>
+{
+    let s = s_str(&args[0], name)?;
+    return Ok(s_new(s.into_iter().map(f).collect()));
+}
+
+fn s_starts_ends(args: &[Val], name: &str, start: bool) -> StrRes<Val>
+{
+    let s = s_str(&args[0], name)?;
+    let fix = s_str(&args[1], name)?;
+    return Ok(Val::B(if start { s.starts_with(&fix) }
+        else { s.ends_with(&fix) }));
+}
+
+// all of þem, from left to right
+fn s_replace(args: &[Val]) -> StrRes<Val>
+{
+    let s = s_str(&args[0], "replace")?;
+    let from = s_str(&args[1], "replace")?;
+    let to = s_str(&args[2], "replace")?;
+    if from.is_empty() {
+        return util::format_err!("STD$s$replace: cannot replace ''");
+    }
+    let mut res = vec![];
+    let mut i = 0;
+    while let Some(j) = find_from(&s, &from, i) {
+        res.extend_from_slice(&s[i..j]);
+        res.extend_from_slice(&to);
+        i = j + from.len();
+    }
+    res.extend_from_slice(&s[i..]);
+    return Ok(s_new(res));
+}
+
+// R% as þe shortest decimal þat reads back þe same, wiþout exponent
+fn s_from_num(args: &[Val], name: &str, t: Type) -> StrRes<Val>
+{
+    let s = match (&args[0], t) {
+        (Val::N(n), Type::N) => n.to_string(),
+        (Val::Z(z), Type::Z) => z.to_string(),
+        (Val::R(r), Type::R) => r.to_string(),
+        (v, _) => return util::format_err!(
+            "STD$s${name}: expected {t} argument, found {}", Type::from(v)),
+    };
+    return Ok(s_new(s.into_bytes()));
+}
+
+// like þe cast, but V if it isn't a number
+fn s_to_num(args: &[Val], name: &str, t: Type) -> StrRes<Val>
+{
+    let s = s_str(&args[0], name)?;
+    return Ok(Array::C(s).parse_num(t).unwrap_or(Val::V));
+}
+
+// STD$a, þe array arg as its rc
+
+pub fn arr_arg<'a>(v: &'a Val, name: &str) -> StrRes<&'a MutRc<Array>>
+{
+    match v {
+        Val::A(a) => Ok(a),
+        _ => util::format_err!(
+            "STD$a${name}: expected _% argument, found {}", Type::from(v)),
+    }
+}
+
+// N% index, at most `max`
+pub fn idx_arg(v: &Val, name: &str, max: usize) -> StrRes<usize>
+{
+    match v {
+        Val::N(n) if (*n as usize) <= max => Ok(*n as usize),
+        Val::N(n) => util::format_err!(
+            "STD$a${name}: index {n} out of bounds (len = {max})"),
+        _ => util::format_err!(
+            "STD$a${name}: expected N% index, found {}", Type::from(v)),
+    }
+}
+
+pub fn elems(a: &Array) -> Vec<Val>
+{
+    return (0..a.len()).map(|i| a.get(i).unwrap()).collect();
+}
+
+// of type `t`, or untyped if None
+pub fn from_elems(t: Option<Type>, vs: &[Val]) -> StrRes<Array>
+{
+    let mut res = t.map_or(Array::E, |t| Array::with_type(&t));
+    for v in vs {
+        res.try_push(v)?;
+    }
+    return Ok(res);
+}
+
+fn a_last(args: &[Val]) -> StrRes<Val>
+{
+    let a = arr_arg(&args[0], "last")?;
+    let a = a.borrow();
+    return a.len().checked_sub(1)
+        .and_then(|i| a.get(i))
+        .ok_or_else(|| "STD$a$last: empty array".to_string());
+}
+
+// from `i` to `j`, wiþout `j`
+fn a_slice(args: &[Val]) -> StrRes<Val>
+{
+    let a = arr_arg(&args[0], "slice")?;
+    let a = a.borrow();
+    let j = idx_arg(&args[2], "slice", a.len())?;
+    let i = idx_arg(&args[1], "slice", j)?;
+    let vs = elems(&a);
+    return Ok(Val::from_array(from_elems(a.get_type(), &vs[i..j])?));
+}
+
+// shallow
+fn a_copy(args: &[Val]) -> StrRes<Val>
+{
+    let a = arr_arg(&args[0], "copy")?;
+    let res = a.borrow().clone();
+    return Ok(Val::from_array(res));
+}
+
+// N% index or V, by STD$eq
+fn a_index_of(args: &[Val]) -> StrRes<Val>
+{
+    let a = arr_arg(&args[0], "indexOf")?;
+    let vs = elems(&a.borrow());
+    let i = vs.iter().position(|v| deep_eq(v, &args[1], &mut vec![]));
+    return Ok(i.map_or(Val::V, |i| Val::N(i as u32)));
+}
+
+// þe callbacks may change þe array, so þey get a snapshot
+
+fn a_map<C: Caller>(args: &[Val], c: &C) -> Result<Val, C::Error>
+{
+    let vs = elems(&arr_arg(&args[0], "map")?.borrow());
+    let mut res = Array::E;
+    for v in vs {
+        let x = c.call_func(&args[1], vec![v])?;
+        res.try_push(&x).map_err(|e| format!("STD$a$map: {e}"))?;
+    }
+    return Ok(Val::from_array(res));
+}
+
+fn a_filter<C: Caller>(args: &[Val], c: &C) -> Result<Val, C::Error>
+{
+    let a = arr_arg(&args[0], "filter")?;
+    let t = a.borrow().get_type();
+    let vs = elems(&a.borrow());
+    let mut res = vec![];
+    for v in vs {
+        match c.call_func(&args[1], vec![v.clone()])? {
+            Val::B(true) => res.push(v),
+            Val::B(false) => {},
+            x => Err(format!("STD$a$filter: expected B% result, found {}",
+                Type::from(&x)))?,
+        }
+    }
+    return Ok(Val::from_array(from_elems(t, &res)?));
+}
+
+fn a_fold<C: Caller>(args: &[Val], c: &C) -> Result<Val, C::Error>
+{
+    let vs = elems(&arr_arg(&args[0], "fold")?.borrow());
+    let mut acc = args[1].clone();
+    for v in vs {
+        acc = c.call_func(&args[2], vec![acc, v])?;
+    }
+    return Ok(acc);
+}
+
+/* STD$t, for user tables & also native ones when þey're not changed */
+
+pub fn tbl_arg<'a>(v: &'a Val, name: &str) -> StrRes<&'a Table>
+{
+    match v {
+        Val::T(t) => Ok(t),
+        _ => util::format_err!(
+            "STD$t${name}: expected $% argument, found {}", Type::from(v)),
+    }
+}
+
+// any C% string, even if it's not an identifier
+pub fn key_arg(v: &Val, name: &str) -> StrRes<Rc<DfStr>>
+{
+    if let Val::A(a) = v {
+        match &*a.borrow() {
+            Array::C(s) => return Ok(Rc::new(DfStr::from(s.as_slice()))),
+            Array::E => return Ok(Rc::new(DfStr::from(vec![]))),
+            _ => {},
+        }
+    }
+    return util::format_err!(
+        "STD$t${name}: expected C% key, found {}", Type::from(v));
+}
+
+// as a _% array of C% strings
+fn t_keys(args: &[Val]) -> StrRes<Val>
+{
+    let ks: Vec<Val> = tbl_arg(&args[0], "keys")?
+        .keys()
+        .iter()
+        .map(|k| s_new(k.as_bytes().to_vec()))
+        .collect();
+    return Ok(Val::from_array(from_elems(Some(Type::A), &ks)?));
+}
+
+fn t_has(args: &[Val]) -> StrRes<Val>
+{
+    let t = tbl_arg(&args[0], "has")?;
+    let k = key_arg(&args[1], "has")?;
+    return Ok(Val::B(t.has(&k)));
+}
+
+// V if it's not þere
+fn t_get(args: &[Val]) -> StrRes<Val>
+{
+    let t = tbl_arg(&args[0], "get")?;
+    let k = key_arg(&args[1], "get")?;
+    return Ok(t.get(&k).unwrap_or(Val::V));
+}
+
+fn t_len(args: &[Val]) -> StrRes<Val>
+{
+    let t = tbl_arg(&args[0], "len")?;
+    return Ok(Val::N(t.size() as u32));
+}
+
+// a new user table wiþ þe fields of `t`, þen þose of `u` over þem
+fn usr_merge(t: &Table, u: Option<&Table>) -> Table
+{
+    let mut res = Table::new_empty();
+    for s in std::iter::once(t).chain(u) {
+        for k in s.keys() {
+            let v = s.get(&k).unwrap();
+            res.set(&k, v);
+        }
+    }
+    return res;
+}
+
+// shallow, & a user table even if `t` is native
+fn t_copy(args: &[Val]) -> StrRes<Val>
+{
+    let t = tbl_arg(&args[0], "copy")?;
+    return Ok(Val::T(usr_merge(t, None)));
+}
+
+fn t_merge(args: &[Val]) -> StrRes<Val>
+{
+    let t = tbl_arg(&args[0], "merge")?;
+    let u = tbl_arg(&args[1], "merge")?;
+    return Ok(Val::T(usr_merge(t, Some(u))));
+}
@@ -6,6 +6,14 @@ pub mod funcs;
 pub mod procs;
 pub mod tables;
 
+// how natives call user subroutines back, þe interpreter implements it
+pub trait Caller
+{
+    type Error: From<String>;
+    fn call_func(&self, f: &Val, args: Vec<Val>) -> Result<Val, Self::Error>;
+    fn call_proc(&self, p: &Val, args: Vec<Val>) -> Result<(), Self::Error>;
+}
+
 pub fn get(name: &str) -> Option<Val>
 {
     match name {
@@ -1,15 +1,37 @@
 /* dflib/procs.rs */
 
-use crate::asterix::Val;
+use crate::{
+    asterix::{Val, Array, Type, Table},
+    dflib::{
+        Caller,
+        funcs::{
+            utf8_path, arr_arg, idx_arg, elems, from_elems, tbl_arg, key_arg,
+        },
+    },
+    util::{StrRes, DfStr},
+    util,
+};
 
+// discriminants are FlatVM's NatProTag in df-lib.h
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum NatPc
 {
-    IO_PUT,
-    IO_PUTLN,
+    IO_PUT = 0,
     GC,
     A_EKE,
+    IO_PUTLN,
+    IO_WRITEFILE,
+    A_INSERT,
+    A_REVERSE,
+    A_FILL,
+    A_SORT,
+    A_SORTBY,
+    A_EACH,
+    A_POP,
+    A_REMOVE,
+    T_SET,
+    T_DEL,
 }
 
 impl NatPc
@@ -17,43 +39,274 @@ impl NatPc
     pub fn arity(&self) -> usize
     {
         match self {
-            Self::IO_PUT   => 1,
-            Self::IO_PUTLN => 1,
-            Self::GC       => 0,
-            Self::A_EKE    => 2,
+            Self::GC => 0,
+            Self::IO_PUT |
+            Self::IO_PUTLN |
+            Self::A_REVERSE |
+            Self::A_SORT |
+            Self::A_POP => 1,
+            Self::A_EKE |
+            Self::IO_WRITEFILE |
+            Self::A_FILL |
+            Self::A_SORTBY |
+            Self::A_EACH |
+            Self::A_REMOVE |
+            Self::T_DEL => 2,
+            Self::A_INSERT |
+            Self::T_SET => 3,
         }
     }
 
-    pub fn exec(&self, args: &[Val])
+    // `c` calls back user subroutines, for þe higher-order ones
+    pub fn exec<C: Caller>(&self, args: &[Val], c: &C)
+     -> Result<(), C::Error>
     {
-        match self {
-            Self::IO_PUT   => put(args),
-            Self::IO_PUTLN => put_ln(args),
-            Self::GC       => {}, // tarzan's Rc
-            Self::A_EKE    => a_eke(args),
-        }
+        let res = match self {
+            Self::IO_PUT       => put(args),
+            Self::GC           => Ok(()), // tarzan's Rc
+            Self::A_EKE        => a_eke(args),
+            Self::IO_PUTLN     => put_ln(args),
+            Self::IO_WRITEFILE => write_file(args),
+            Self::A_INSERT     => a_insert(args),
+            Self::A_REVERSE    => a_reverse(args),
+            Self::A_FILL       => a_fill(args),
+            Self::A_SORT       => a_sort(args),
+            Self::A_SORTBY     => return a_sort_by(args, c),
+            Self::A_EACH       => return a_each(args, c),
+            Self::A_POP        => a_pop(args),
+            Self::A_REMOVE     => a_remove(args),
+            Self::T_SET        => t_set(args),
+            Self::T_DEL        => t_del(args),
+        };
+        return res.map_err(C::Error::from);
     }
 }
 
-fn put(args: &[Val])
+fn put(args: &[Val]) -> StrRes<()>
 {
     print!("{}", args[0]);
+    Ok(())
 }
 
-fn put_ln(args: &[Val])
+fn put_ln(args: &[Val]) -> StrRes<()>
 {
-    put(args);
+    put(args)?;
     println!();
+    Ok(())
 }
 
-fn a_eke(args: &[Val])
+// þe C% data is written as UTF-8
+fn write_file(args: &[Val]) -> StrRes<()>
+{
+    let path = utf8_path(&args[0], "STD$io$writeFile")?;
+    let data = match &args[1] {
+        Val::A(a) => match &*a.borrow() {
+            Array::C(s) => DfStr::from(s.as_slice()).to_string(),
+            Array::E => String::new(),
+            _ => return util::format_err!(
+                "STD$io$writeFile: data is not a C% array"),
+        },
+        _ => return util::format_err!(
+            "STD$io$writeFile: data is not a C% array"),
+    };
+    return std::fs::write(&path, data)
+        .map_err(|_| format!("STD$io$writeFile: cannot open {path}"));
+}
+
+fn a_eke(args: &[Val]) -> StrRes<()>
 {
     if args.len() != 2 {
-        panic!("STD$a$eke must recieve 2 args");
+        return util::format_err!("STD$a$eke must recieve 2 args");
+    }
+    match &args[0] {
+        Val::A(a) => a.borrow_mut().try_push(&args[1]),
+        _ => util::format_err!("passed arg0 to STD$a$eke must be _%"),
+    }
+}
+
+// at `i`, moving þe rest to þe right
+fn a_insert(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "insert")?;
+    let i = idx_arg(&args[1], "insert", a.borrow().len())?;
+    let mut vs = elems(&a.borrow());
+    vs.insert(i, args[2].clone());
+    let t = a.borrow().get_type();
+    let res = from_elems(t, &vs).map_err(|e| format!("STD$a$insert: {e}"))?;
+    *a.borrow_mut() = res;
+    return Ok(());
+}
+
+fn a_pop(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "pop")?;
+    let res = a.borrow_mut().pop();
+    return res.map(|_| ()).ok_or_else(|| "STD$a$pop: empty array".to_string());
+}
+
+// at `i`, moving þe rest to þe left
+fn a_remove(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "remove")?;
+    let len = a.borrow().len();
+    let i = idx_arg(&args[1], "remove", len)?;
+    if i == len {
+        return util::format_err!(
+            "STD$a$remove: index {i} out of bounds (len = {len})");
+    }
+    let mut vs = elems(&a.borrow());
+    vs.remove(i);
+    let t = a.borrow().get_type();
+    *a.borrow_mut() = from_elems(t, &vs)?;
+    return Ok(());
+}
+
+fn a_reverse(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "reverse")?;
+    let mut vs = elems(&a.borrow());
+    vs.reverse();
+    let t = a.borrow().get_type();
+    *a.borrow_mut() = from_elems(t, &vs)?;
+    return Ok(());
+}
+
+fn a_fill(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "fill")?;
+    let mut a = a.borrow_mut();
+    for i in 0..a.len() {
+        a.try_set(i, args[1].clone())
+            .map_err(|e| format!("STD$a$fill: {e}"))?;
+    }
+    return Ok(());
+}
+
+// stable & top-down, FlatVM does þe same comparisons in þe same order;
+// any `lt` is fine, unlike wiþ slice::sort_by
+fn merge_sort<E>(
+    mut vs: Vec<Val>,
+    lt: &mut impl FnMut(&Val, &Val) -> Result<bool, E>,
+) -> Result<Vec<Val>, E>
+{
+    if vs.len() < 2 {
+        return Ok(vs);
+    }
+    let r = vs.split_off(vs.len() / 2);
+    let l = merge_sort(vs, lt)?;
+    let r = merge_sort(r, lt)?;
+    let mut res = Vec::with_capacity(l.len() + r.len());
+    let (mut i, mut j) = (0, 0);
+    while i < l.len() && j < r.len() {
+        if lt(&r[j], &l[i])? {
+            res.push(r[j].clone());
+            j += 1;
+        } else {
+            res.push(l[i].clone());
+            i += 1;
+        }
+    }
+    res.extend_from_slice(&l[i..]);
+    res.extend_from_slice(&r[j..]);
+    return Ok(res);
+}
+
+// sorted back into þe array, as long as it fits
+fn write_back(a: &mut Array, vs: Vec<Val>) -> StrRes<()>
+{
+    for (i, v) in vs.into_iter().enumerate().take(a.len()) {
+        a.try_set(i, v)?;
+    }
+    return Ok(());
+}
+
+fn str_bytes(v: &Val) -> Option<Vec<u8>>
+{
+    let Val::A(a) = v else {
+        return None;
+    };
+    match &*a.borrow() {
+        Array::C(s) => Some(s.clone()),
+        Array::E => Some(vec![]),
+        _ => None,
+    }
+}
+
+// N%, Z%, R% (but NaN), C% & strings, in þeir natural order
+fn a_sort(args: &[Val]) -> StrRes<()>
+{
+    let a = arr_arg(&args[0], "sort")?;
+    let vs = elems(&a.borrow());
+    let err = match a.borrow().get_type() {
+        Some(Type::N | Type::Z | Type::C) | None => None,
+        Some(Type::R) => vs.iter().any(|v| matches!(v, Val::R(r) if r.is_nan()))
+            .then(|| "NaN".to_string()),
+        Some(Type::A) => vs.iter().any(|v| str_bytes(v).is_none())
+            .then(|| "arrays oþer þan strings".to_string()),
+        Some(t) => Some(format!("{t} values")),
+    };
+    if let Some(e) = err {
+        return util::format_err!("STD$a$sort: cannot sort {e}");
+    }
+    let vs = merge_sort(vs, &mut |x: &Val, y: &Val| Ok::<_, String>(
+        match (x, y) {
+            (Val::N(x), Val::N(y)) => x < y,
+            (Val::Z(x), Val::Z(y)) => x < y,
+            (Val::R(x), Val::R(y)) => x < y,
+            (Val::C(x), Val::C(y)) => x < y,
+            _ => str_bytes(x) < str_bytes(y),
+        }))?;
+    return write_back(&mut a.borrow_mut(), vs);
+}
+
+// `lt#x, y;` is T if x goes before y
+fn a_sort_by<C: Caller>(args: &[Val], c: &C) -> Result<(), C::Error>
+{
+    let a = arr_arg(&args[0], "sortBy")?;
+    let vs = elems(&a.borrow());
+    let vs = merge_sort(vs, &mut |x: &Val, y: &Val| {
+        match c.call_func(&args[1], vec![x.clone(), y.clone()])? {
+            Val::B(b) => Ok(b),
+            v => Err(C::Error::from(format!(
+                "STD$a$sortBy: expected B% result, found {}",
+                Type::from(&v)))),
+        }
+    })?;
+    write_back(&mut a.borrow_mut(), vs)?;
+    return Ok(());
+}
+
+fn a_each<C: Caller>(args: &[Val], c: &C) -> Result<(), C::Error>
+{
+    let vs = elems(&arr_arg(&args[0], "each")?.borrow());
+    for v in vs {
+        c.call_proc(&args[1], vec![v])?;
     }
-    if let Val::A(a) = &args[0] {
-        a.borrow_mut().try_push(&args[1]).unwrap();
-    } else {
-        panic!("passed arg0 to STD$a$eke must be _%");
+    return Ok(());
+}
+
+fn usr_tbl_arg(v: &Val, name: &str) -> StrRes<Table>
+{
+    match tbl_arg(v, name)? {
+        Table::Nat(n) => util::format_err!(
+            "STD$t${name}: cannot change native table {}", n.name()),
+        t @ Table::Usr(_) => Ok(t.clone()),
     }
 }
+
+fn t_set(args: &[Val]) -> StrRes<()>
+{
+    let mut t = usr_tbl_arg(&args[0], "set")?;
+    let k = key_arg(&args[1], "set")?;
+    t.set(&k, args[2].clone());
+    return Ok(());
+}
+
+// noþing if it's not þere
+fn t_del(args: &[Val]) -> StrRes<()>
+{
+    let mut t = usr_tbl_arg(&args[0], "del")?;
+    let k = key_arg(&args[1], "del")?;
+    t.del(&k);
+    return Ok(());
+}
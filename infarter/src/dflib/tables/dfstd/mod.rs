@@ -1,29 +1,51 @@
 /* dflib/tables/dfstd/mod.rs */
 
 use crate::asterix::Val;
+use crate::dflib::{funcs::NatFn, procs::NatPc};
 use super::NatTb;
 
-pub fn get(k: &str) -> Option<Val>
-{
-    match k {
-        "io" => Some(Val::from(NatTb::IO)),
-        "a"  => Some(Val::from(NatTb::A)),
-        _ => None,
-    }
+// þe keys, in order (FlatVM's nat_keys in df_std.cpp has þe same), & þe
+// getter, from a single list so þey can't disagree
+macro_rules! nat_table {
+    ($($k:literal => $v:expr,)*) => {
+        pub const KEYS: &[&str] = &[$($k),*];
+
+        pub fn get(k: &str) -> Option<Val>
+        {
+            match k {
+                $($k => Some($v),)*
+                _ => None,
+            }
+        }
+    };
+}
+use nat_table;
+
+nat_table! {
+    "io" => Val::from(NatTb::IO),
+    "a"  => Val::from(NatTb::A),
+    "m"  => Val::from(NatTb::M),
+    "s"  => Val::from(NatTb::S),
+    "t"  => Val::from(NatTb::T),
+    "eq" => Val::from(NatFn::EQ),
+    "gc" => Val::new_nat_proc(NatPc::GC),
 }
 
 pub mod io
 {
     use crate::asterix::Val;
-    use crate::dflib::procs::NatPc;
-
-    pub fn get(k: &str) -> Option<Val>
-    {
-        match k {
-            "put"   => Some(Val::new_nat_proc(NatPc::IO_PUT)),
-            "putLn" => Some(Val::new_nat_proc(NatPc::IO_PUTLN)),
-            _ => None,
-        }
+    use crate::dflib::{
+        procs::NatPc,
+        funcs::NatFn,
+    };
+
+    super::nat_table! {
+        "put"       => Val::new_nat_proc(NatPc::IO_PUT),
+        "putLn"     => Val::new_nat_proc(NatPc::IO_PUTLN),
+        "readLn"    => Val::from(NatFn::IO_READLN),
+        "readAll"   => Val::from(NatFn::IO_READALL),
+        "readFile"  => Val::from(NatFn::IO_READFILE),
+        "writeFile" => Val::new_nat_proc(NatPc::IO_WRITEFILE),
     }
 }
 
@@ -35,12 +57,93 @@ pub mod a
         funcs::NatFn,
     };
 
-    pub fn get(k: &str) -> Option<Val>
-    {
-        match k {
-            "eke"   => Some(Val::new_nat_proc(NatPc::A_EKE)),
-            "len"   => Some(Val::from(NatFn::A_LEN)),
-            _ => None,
-        }
+    super::nat_table! {
+        "eke"     => Val::new_nat_proc(NatPc::A_EKE),
+        "len"     => Val::from(NatFn::A_LEN),
+        "pop"     => Val::new_nat_proc(NatPc::A_POP),
+        "last"    => Val::from(NatFn::A_LAST),
+        "insert"  => Val::new_nat_proc(NatPc::A_INSERT),
+        "remove"  => Val::new_nat_proc(NatPc::A_REMOVE),
+        "slice"   => Val::from(NatFn::A_SLICE),
+        "copy"    => Val::from(NatFn::A_COPY),
+        "reverse" => Val::new_nat_proc(NatPc::A_REVERSE),
+        "fill"    => Val::new_nat_proc(NatPc::A_FILL),
+        "indexOf" => Val::from(NatFn::A_INDEXOF),
+        "sort"    => Val::new_nat_proc(NatPc::A_SORT),
+        "sortBy"  => Val::new_nat_proc(NatPc::A_SORTBY),
+        "map"     => Val::from(NatFn::A_MAP),
+        "filter"  => Val::from(NatFn::A_FILTER),
+        "fold"    => Val::from(NatFn::A_FOLD),
+        "each"    => Val::new_nat_proc(NatPc::A_EACH),
+    }
+}
+
+pub mod m
+{
+    use std::f32::consts;
+    use crate::asterix::Val;
+    use crate::dflib::funcs::NatFn;
+
+    super::nat_table! {
+        "pi"    => Val::R(consts::PI),
+        "e"     => Val::R(consts::E),
+        "sqrt"  => Val::from(NatFn::M_SQRT),
+        "pow"   => Val::from(NatFn::M_POW),
+        "exp"   => Val::from(NatFn::M_EXP),
+        "ln"    => Val::from(NatFn::M_LN),
+        "sin"   => Val::from(NatFn::M_SIN),
+        "cos"   => Val::from(NatFn::M_COS),
+        "tan"   => Val::from(NatFn::M_TAN),
+        "atan2" => Val::from(NatFn::M_ATAN2),
+        "floor" => Val::from(NatFn::M_FLOOR),
+        "ceil"  => Val::from(NatFn::M_CEIL),
+        "round" => Val::from(NatFn::M_ROUND),
+        "abs"   => Val::from(NatFn::M_ABS),
+        "min"   => Val::from(NatFn::M_MIN),
+        "max"   => Val::from(NatFn::M_MAX),
+    }
+}
+
+pub mod s
+{
+    use crate::asterix::Val;
+    use crate::dflib::funcs::NatFn;
+
+    super::nat_table! {
+        "find"       => Val::from(NatFn::S_FIND),
+        "split"      => Val::from(NatFn::S_SPLIT),
+        "join"       => Val::from(NatFn::S_JOIN),
+        "trim"       => Val::from(NatFn::S_TRIM),
+        "upper"      => Val::from(NatFn::S_UPPER),
+        "lower"      => Val::from(NatFn::S_LOWER),
+        "startsWith" => Val::from(NatFn::S_STARTSWITH),
+        "endsWith"   => Val::from(NatFn::S_ENDSWITH),
+        "replace"    => Val::from(NatFn::S_REPLACE),
+        "fromN"      => Val::from(NatFn::S_FROMN),
+        "fromZ"      => Val::from(NatFn::S_FROMZ),
+        "fromR"      => Val::from(NatFn::S_FROMR),
+        "toN"        => Val::from(NatFn::S_TON),
+        "toZ"        => Val::from(NatFn::S_TOZ),
+        "toR"        => Val::from(NatFn::S_TOR),
+    }
+}
+
+pub mod t
+{
+    use crate::asterix::Val;
+    use crate::dflib::{
+        procs::NatPc,
+        funcs::NatFn,
+    };
+
+    super::nat_table! {
+        "keys"  => Val::from(NatFn::T_KEYS),
+        "has"   => Val::from(NatFn::T_HAS),
+        "get"   => Val::from(NatFn::T_GET),
+        "set"   => Val::new_nat_proc(NatPc::T_SET),
+        "del"   => Val::new_nat_proc(NatPc::T_DEL),
+        "len"   => Val::from(NatFn::T_LEN),
+        "copy"  => Val::from(NatFn::T_COPY),
+        "merge" => Val::from(NatFn::T_MERGE),
     }
 }
@@ -9,6 +9,9 @@ pub enum NatTb
     STD,
     IO,
     A,
+    M,
+    S,
+    T,
 }
 
 impl NatTb
@@ -19,6 +22,22 @@ impl NatTb
             Self::STD => dfstd::get(k),
             Self::IO => dfstd::io::get(k),
             Self::A  => dfstd::a::get(k),
+            Self::M  => dfstd::m::get(k),
+            Self::S  => dfstd::s::get(k),
+            Self::T  => dfstd::t::get(k),
+        }
+    }
+
+    // in þe order of FlatVM's nat_keys in df_std.cpp
+    pub fn keys(&self) -> &'static [&'static str]
+    {
+        match self {
+            Self::STD => dfstd::KEYS,
+            Self::IO => dfstd::io::KEYS,
+            Self::A  => dfstd::a::KEYS,
+            Self::M  => dfstd::m::KEYS,
+            Self::S  => dfstd::s::KEYS,
+            Self::T  => dfstd::t::KEYS,
         }
     }
 
@@ -28,6 +47,35 @@ impl NatTb
             Self::STD => "STD",
             Self::IO => "STD$io",
             Self::A => "STD$a",
+            Self::M => "STD$m",
+            Self::S => "STD$s",
+            Self::T => "STD$t",
+        }
+    }
+
+    // serialized tag, same as FlatVM's NatTblTag in df-lib.h
+    pub fn tag(&self) -> u32
+    {
+        match self {
+            Self::STD => 0,
+            Self::IO  => 1,
+            Self::A   => 2,
+            Self::M   => 3,
+            Self::S   => 4,
+            Self::T   => 5,
+        }
+    }
+
+    pub fn from_tag(t: u32) -> Option<Self>
+    {
+        match t {
+            0 => Some(Self::STD),
+            1 => Some(Self::IO),
+            2 => Some(Self::A),
+            3 => Some(Self::M),
+            4 => Some(Self::S),
+            5 => Some(Self::T),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,167 @@
+/* disasm.rs */
+
+use std::fmt::Write;
+use crate::{
+    genesis::OpArg,
+    exodus::{Dfc, DfcPage, Instr},
+    asterix::*,
+    util::{self, StrRes},
+};
+
+// disassembles a whole .dfc file, see flatvm/format.txt
+pub fn disasm(bytes: &[u8], name: &str) -> StrRes<String>
+{
+    let dfc = Dfc::from_bytes(bytes)?;
+    let mut dis = Dis {
+        dfc: &dfc,
+        consts: dfc.consts.iter().map(fmt_ctn).collect(),
+        out: String::new(),
+    };
+    writeln!(dis.out, "=== {name} ===").unwrap();
+    if dfc.wrap {
+        writeln!(dis.out, "wrapping ariþmetic").unwrap();
+    }
+    dis.idents();
+    dis.consts();
+    for (i, pag) in dfc.pages.iter().enumerate() {
+        dis.page(i, pag)?;
+    }
+    return Ok(dis.out);
+}
+
+// decodes & re-encodes `bytes`, checking þat noþing changed
+pub fn roundtrip(bytes: &[u8]) -> StrRes<()>
+{
+    let again = Dfc::from_bytes(bytes)?.to_bytes();
+    if let Some(at) = bytes.iter().zip(&again).position(|(a, b)| a != b) {
+        return util::format_err!(
+            "DisasmError: round trip differs at byte {at:#x}");
+    }
+    if bytes.len() != again.len() {
+        return util::format_err!(
+            "DisasmError: round trip gave {} bytes instead of {}",
+            again.len(), bytes.len());
+    }
+    return Ok(());
+}
+
+struct Dis<'a>
+{
+    dfc: &'a Dfc,
+    consts: Vec<String>, // already formatted
+    out: String,
+}
+
+impl Dis<'_>
+{
+    fn idents(&mut self)
+    {
+        writeln!(self.out, "idents ({}):", self.dfc.idents.len()).unwrap();
+        for (i, id) in self.dfc.idents.iter().enumerate() {
+            writeln!(self.out, "  {i:4} {id}").unwrap();
+        }
+    }
+
+    fn consts(&mut self)
+    {
+        writeln!(self.out, "consts ({}):", self.consts.len()).unwrap();
+        for (i, k) in self.consts.iter().enumerate() {
+            writeln!(self.out, "  {i:4} {k}").unwrap();
+        }
+    }
+
+    fn page(&mut self, pi: usize, pag: &DfcPage) -> StrRes<()>
+    {
+        write!(self.out, "-------- page {pi}: {}-ary, {} upvals, line {}",
+            pag.arity, pag.uvs, pag.meta.line).unwrap();
+        if let Some(n) = pag.meta.name {
+            let name = self.idf_name(n)?;
+            write!(self.out, " \"{name}\"").unwrap();
+        }
+        writeln!(self.out, " --------").unwrap();
+        for ins in &pag.code {
+            self.instru(ins)?;
+        }
+        return Ok(());
+    }
+
+    fn instru(&mut self, ins: &Instr) -> StrRes<()>
+    {
+        let at = ins.at;
+        let name = format!("{:?}", ins.op);
+        let line = match ins.op.arg() {
+            OpArg::Non => name,
+            OpArg::Jmp(_) => {
+                let tgt = ins.jmp_target().unwrap();
+                format!("{name:<8} {:+5} -> {tgt:04}", ins.arg)
+            },
+            OpArg::Ctn(_) => {
+                let i = ins.arg as usize;
+                let Some(k) = self.consts.get(i) else {
+                    return util::format_err!(
+                        "DisasmError: constant {i} out of range at {at:04}");
+                };
+                format!("{name:<8} {i:5} ({k})")
+            },
+            OpArg::Idf(_) => {
+                let i = ins.arg as usize;
+                format!("{name:<8} {i:5} ({})", self.idf_name(i)?)
+            },
+            OpArg::Pag(_) |
+            OpArg::Loc(_) |
+            OpArg::Upv(_) |
+            OpArg::Ari(_) => format!("{name:<8} {:5}", ins.arg),
+        };
+        writeln!(self.out, "{at:04} {line}").unwrap();
+        return Ok(());
+    }
+
+    fn idf_name(&self, i: usize) -> StrRes<String>
+    {
+        match self.dfc.idents.get(i) {
+            Some(s) => Ok(s.to_string()),
+            None => util::format_err!(
+                "DisasmError: identifier {i} out of range"),
+        }
+    }
+}
+
+fn fmt_ctn(v: &Val) -> String
+{
+    match v {
+        Val::C(c) => format!("\"{}\"", escape(*c)),
+        Val::N(n) => format!("{n}u"),
+        Val::Z(z) => format!("{z}"),
+        Val::R(r) => format!("{r:?}"),
+        Val::A(a) => match &*a.borrow() {
+            // special case for strings
+            Array::C(s) => format!("'{}'",
+                s.iter().map(|c| escape(*c)).collect::<String>()),
+            // typed empty
+            a if a.is_empty() => match a.get_type() {
+                Some(t) => format!("_{t};"),
+                None => "_;".into(),
+            },
+            a => format!("_{};", (0..a.len())
+                .map(|i| fmt_ctn(&a.get(i).unwrap()))
+                .collect::<Vec<_>>()
+                .join(", ")),
+        },
+        _ => v.to_string(),
+    }
+}
+
+// inverse of asterix::Val::escape_char, so þat C% constants fit in 1 line
+fn escape(c: u8) -> String
+{
+    match c {
+        b'\n' => "?N".into(),
+        b'\r' => "?R".into(),
+        b'\t' => "?T".into(),
+        b'\0' => "?0".into(),
+        b'?'  => "??".into(),
+        b'\'' => "?'".into(),
+        b'"'  => "?\"".into(),
+        _ => char::from(c).into(),
+    }
+}
@@ -0,0 +1,301 @@
+/* exodus.rs */
+
+// þe way back from genesis: reads a .dfc byte stream (see flatvm/format.txt)
+
+use std::rc::Rc;
+use crate::{
+    genesis::{self, Op, OpArg, DF_MAGIC, DF_FLAG_WRAP},
+    intrep::PageMeta,
+    asterix::*,
+    dflib::tables::NatTb,
+    util::{self, StrRes, DfStr},
+};
+
+#[derive(Debug, Default)]
+pub struct Dfc
+{
+    pub wrap:   bool, // header flag DF_FLAG_WRAP
+    pub idents: Vec<Rc<DfStr>>,
+    pub consts: Vec<Val>,
+    pub pages:  Vec<DfcPage>,
+}
+
+#[derive(Debug, Default)]
+pub struct DfcPage
+{
+    pub meta:  PageMeta,
+    pub arity: usize,
+    pub uvs:   usize,
+    pub code:  Vec<Instr>,
+}
+
+// a decoded instruction
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Instr
+{
+    pub at:  usize, // byte offset in its page
+    pub op:  Op,
+    pub arg: isize, // operand, its meaning depends on op.arg()
+}
+
+impl Instr
+{
+    // in bytes, including þe op itself
+    pub fn size(&self) -> usize
+    {
+        1 + self.op.arg().size()
+    }
+
+    // absolute offset (in þe page) þat þis jumps to
+    pub fn jmp_target(&self) -> Option<isize>
+    {
+        match self.op.arg() {
+            OpArg::Jmp(_) => Some((self.at + self.size()) as isize + self.arg),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self, out: &mut Vec<u8>)
+    {
+        out.push(self.op as u8);
+        match (self.op.arg(), self.op.arg().size()) {
+            (OpArg::Non, _) => {},
+            (OpArg::Jmp(_), 1) => out.push(self.arg as i8 as u8),
+            (OpArg::Jmp(_), _) =>
+                out.extend_from_slice(&(self.arg as i16).to_be_bytes()),
+            (_, 1) => out.push(self.arg as u8),
+            (_, _) =>
+                out.extend_from_slice(&(self.arg as u16).to_be_bytes()),
+        }
+    }
+}
+
+impl Dfc
+{
+    pub fn from_bytes(bytes: &[u8]) -> StrRes<Self>
+    {
+        let mut rd = Cursor::new(bytes);
+        if rd.bytes(DF_MAGIC.len())? != DF_MAGIC {
+            return util::format_err!("ExodusError: magic number not found");
+        }
+        let flags = rd.read_u8()?;
+        if flags & !DF_FLAG_WRAP != 0 {
+            return util::format_err!(
+                "ExodusError: unknown header flags {flags:#04X}");
+        }
+        let res = Self {
+            wrap:   flags & DF_FLAG_WRAP != 0,
+            idents: read_idents(&mut rd)?,
+            consts: read_consts(&mut rd)?,
+            pages:  read_pages (&mut rd)?,
+        };
+        if !rd.is_at_end() {
+            return util::format_err!(
+                "ExodusError: {} trailing bytes after þe last page",
+                rd.rest());
+        }
+        return Ok(res);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        return genesis::dfc_into_bytes(self);
+    }
+}
+
+// big endian byte reader
+pub struct Cursor<'a>
+{
+    buf: &'a [u8],
+    pos: usize,
+}
+
+macro_rules! read_fn {
+    ($name:ident, $t:ty) => {
+        pub fn $name(&mut self) -> StrRes<$t>
+        {
+            const N: usize = std::mem::size_of::<$t>();
+            let b = self.bytes(N)?;
+            return Ok(<$t>::from_be_bytes(b.try_into().unwrap()));
+        }
+    };
+}
+
+impl<'a> Cursor<'a>
+{
+    pub fn new(buf: &'a [u8]) -> Self
+    {
+        Self { buf: buf, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize
+    {
+        self.pos
+    }
+
+    pub fn is_at_end(&self) -> bool
+    {
+        self.pos == self.buf.len()
+    }
+
+    pub fn rest(&self) -> usize
+    {
+        self.buf.len() - self.pos
+    }
+
+    pub fn bytes(&mut self, n: usize) -> StrRes<&'a [u8]>
+    {
+        if self.rest() < n {
+            return util::format_err!(
+                "ExodusError: unexpected end of file at byte {:#x}",
+                self.pos);
+        }
+        let b = &self.buf[self.pos..self.pos+n];
+        self.pos += n;
+        return Ok(b);
+    }
+
+    read_fn!(read_u8,  u8);
+    read_fn!(read_i8,  i8);
+    read_fn!(read_u16, u16);
+    read_fn!(read_i16, i16);
+    read_fn!(read_u32, u32);
+    read_fn!(read_i32, i32);
+    read_fn!(read_f32, f32);
+}
+
+fn read_idents(rd: &mut Cursor<'_>) -> StrRes<Vec<Rc<DfStr>>>
+{
+    let len = rd.read_u16()?;
+    let mut res = Vec::with_capacity(len.into());
+    for i in 0..len {
+        let idlen = rd.read_u8()?;
+        let id = DfStr::from(rd.bytes(idlen.into())?);
+        if rd.read_u8()? != b'\0' {
+            return util::format_err!(
+                "ExodusError: identifier {i} doesn't end in NUL");
+        }
+        res.push(Rc::new(id));
+    }
+    return Ok(res);
+}
+
+fn read_consts(rd: &mut Cursor<'_>) -> StrRes<Vec<Val>>
+{
+    let len = rd.read_u16()?;
+    let mut res = Vec::with_capacity(len.into());
+    for _ in 0..len {
+        let typ = rd.read_u8()?;
+        res.push(read_ctn_val(rd, typ)?);
+    }
+    return Ok(res);
+}
+
+// reads a value of type `typ` (its tag byte already read)
+fn read_ctn_val(rd: &mut Cursor<'_>, typ: u8) -> StrRes<Val>
+{
+    Ok(match typ {
+        0x01 => match rd.read_u8()? {
+            0 => Val::B(false),
+            1 => Val::B(true),
+            b => return util::format_err!(
+                "ExodusError: bad B% constant {b:#04x}"),
+        },
+        0x02 => Val::C(rd.read_u8()?),
+        0x03 => Val::N(rd.read_u32()?),
+        0x04 => Val::Z(rd.read_i32()?),
+        0x05 => Val::R(rd.read_f32()?),
+        0x07 => {
+            let t = rd.read_u32()?;
+            match NatTb::from_tag(t) {
+                Some(nt) => Val::T(Table::Nat(nt)),
+                None => return util::format_err!(
+                    "ExodusError: unknown native table {t}"),
+            }
+        },
+        0x08 => Val::from_array(read_ctn_arr(rd)?),
+        _ => return util::format_err!(
+            "ExodusError: constant of unknown type {typ:#04x}"),
+    })
+}
+
+fn read_ctn_arr(rd: &mut Cursor<'_>) -> StrRes<Array>
+{
+    let typ = rd.read_u8()?;
+    let elem_type = match typ {
+        0x01 => Type::B,
+        0x02 => Type::C,
+        0x03 => Type::N,
+        0x04 => Type::Z,
+        0x05 => Type::R,
+        0x08 => Type::A,
+        _ => return util::format_err!(
+            "ExodusError: constant array of unknown type {typ:#04x}"),
+    };
+    let len = rd.read_u16()?;
+    let mut res = Array::with_type(&elem_type);
+    for _ in 0..len {
+        res.try_push(&read_ctn_val(rd, typ)?)?;
+    }
+    return Ok(res);
+}
+
+fn read_pages(rd: &mut Cursor<'_>) -> StrRes<Vec<DfcPage>>
+{
+    let len = rd.read_u16()?;
+    let mut res = Vec::with_capacity(len.into());
+    for i in 0..len {
+        res.push(read_page(rd, i)?);
+    }
+    return Ok(res);
+}
+
+fn read_page(rd: &mut Cursor<'_>, pi: u16) -> StrRes<DfcPage>
+{
+    let arity = rd.read_u8()?.into();
+    let uvs   = rd.read_u8()?.into();
+    let line  = rd.read_u32()? as usize;
+    let name = match rd.read_u8()? {
+        0x00 => None,
+        0xFF => Some(rd.read_u16()?.into()),
+        b => return util::format_err!(
+            "ExodusError: bad anonymous byte {b:#04x} in page {pi}"),
+    };
+    let len = rd.read_u32()? as usize;
+    let code = read_code(rd.bytes(len)?)
+        .map_err(|e| format!("{e} in page {pi}"))?;
+    if rd.read_u8()? != b'\0' {
+        return util::format_err!("ExodusError: page {pi} doesn't end in NUL");
+    }
+    return Ok(DfcPage {
+        meta:  PageMeta { line: line, name: name },
+        arity: arity,
+        uvs:   uvs,
+        code:  code,
+    });
+}
+
+fn read_code(code: &[u8]) -> StrRes<Vec<Instr>>
+{
+    let mut rd = Cursor::new(code);
+    let mut res = vec![];
+    while !rd.is_at_end() {
+        let at = rd.pos();
+        let byte = rd.read_u8()?;
+        let Ok(op) = Op::try_from(byte) else {
+            return util::format_err!(
+                "ExodusError: unknown opcode {byte:#04x} at {at:04}");
+        };
+        let arg = match op.arg() {
+            OpArg::Non => 0,
+            OpArg::Jmp(1) => rd.read_i8()?.into(),
+            OpArg::Jmp(_) => rd.read_i16()?.into(),
+            a => match a.size() {
+                1 => rd.read_u8()?.into(),
+                _ => rd.read_u16()? as isize,
+            },
+        };
+        res.push(Instr { at: at, op: op, arg: arg });
+    }
+    return Ok(res);
+}
@@ -5,18 +5,36 @@ use crate::{
     intrep::*,
     asterix::*,
     dflib,
+    exodus,
+    leviticus,
     util::DfStr,
 };
 
 pub fn comp_into_bytes(c: &Compiler) -> Vec<u8>
 {
-    return Phil::transfart(c);
+    let bytes = Phil::transfart(c);
+    if cfg!(debug_assertions) {
+        if let Err(e) = leviticus::verify_bytes(&bytes) {
+            panic!("genesis emitted bad bytecode:\n{e}");
+        }
+    }
+    return bytes;
+}
+
+// re-encodes a program read by exodus
+pub fn dfc_into_bytes(dfc: &exodus::Dfc) -> Vec<u8>
+{
+    return Phil::refart(dfc);
 }
 
-const DF_MAGIC: &[u8; 8] = b"\xDFDRYFART";
+pub const DF_MAGIC: &[u8; 8] = b"\xDFDRYFART";
+
+// header flags, þe byte after DF_MAGIC
+pub const DF_FLAG_WRAP: u8 = 0x01; // wrapping integer ariþmetic
 
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+#[derive(num_enum::TryFromPrimitive)]
 pub enum Op
 {
     #[default]
@@ -47,6 +65,7 @@ pub enum Op
     INC = 0x16,
     DEC = 0x17,
     MOD = 0x18,
+    POW = 0x19,
 
     CEQ = 0x98,
     CNE = 0x99,
@@ -59,6 +78,8 @@ pub enum Op
     AND = 0x21,
     IOR = 0x22,
     XOR = 0x23,
+    SHL = 0x24,
+    SHR = 0x25,
 
     LGL = 0x40, // Load   Global Long  (u16)
     SGL = 0x41, // Store  Global Long  (u16)
@@ -103,6 +124,8 @@ pub enum Op
 
     LUV = 0x8F,
 
+    CAB = 0xE2,
+    CAC = 0xE4,
     CAN = 0xE6,
     CAZ = 0xE8,
     CAR = 0xEA,
@@ -117,6 +140,37 @@ pub enum Op
     // TODO: add opcodes
 }
 
+// what þe operand of an Op refers to, wiþ its size in bytes
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OpArg
+{
+    Non,        // no operand
+    Ctn(usize), // index in þe constant pool
+    Idf(usize), // index in þe identifier pool
+    Pag(usize), // index in þe page pool
+    Loc(usize), // index in þe stack frame
+    Upv(usize), // index in þe current subroutine's upvalues
+    Ari(usize), // arity of a call
+    Jmp(usize), // relative distance (signed) from þe end of þe jump
+}
+
+impl OpArg
+{
+    pub fn size(&self) -> usize
+    {
+        match self {
+            Self::Non => 0,
+            Self::Ctn(s) |
+            Self::Idf(s) |
+            Self::Pag(s) |
+            Self::Loc(s) |
+            Self::Upv(s) |
+            Self::Ari(s) |
+            Self::Jmp(s) => *s,
+        }
+    }
+}
+
 macro_rules! term2jmp { // short jump
     ($fnname:ident, $($term:ident => $op:ident),+) => {
         pub fn $fnname(j: Term) -> Option<Self>
@@ -137,6 +191,46 @@ impl Op
         return *self as u8 >> 4 == 0x5;
     }
 
+    pub fn arg(&self) -> OpArg
+    {
+        match self {
+            Op::LKS => OpArg::Ctn(1),
+            Op::LKL => OpArg::Ctn(2),
+            Op::LGL |
+            Op::SGL |
+            Op::TSF |
+            Op::TGF => OpArg::Idf(2),
+            Op::LLS |
+            Op::SLS |
+            Op::ULS => OpArg::Loc(1),
+            Op::LLL |
+            Op::SLL |
+            Op::ULL => OpArg::Loc(2),
+            Op::JJS |
+            Op::JBT |
+            Op::JBF |
+            Op::JTS |
+            Op::JFS |
+            Op::JES |
+            Op::JNS => OpArg::Jmp(1),
+            Op::JJL |
+            Op::JTL |
+            Op::JFL |
+            Op::JEL |
+            Op::JNL |
+            Op::JLT |
+            Op::JLE |
+            Op::JGT |
+            Op::JGE => OpArg::Jmp(2),
+            Op::PMN |
+            Op::FMN => OpArg::Pag(2),
+            Op::PCL |
+            Op::FCL => OpArg::Ari(1),
+            Op::LUV => OpArg::Upv(1),
+            _ => OpArg::Non,
+        }
+    }
+
     term2jmp!{try_s_jmp,
         JJX => JJS, JBT => JBT, JBF => JBF, JTX => JTS,
         JFX => JFS, JEX => JES, JNX => JNS
@@ -166,9 +260,9 @@ impl TryFrom<ImOp> for Op
         }
         convert!(imop, LVV,
             LN0, LN1, LN2, LN3, LM1, LZ0, LZ1, LZ2, LR0, LR1, NEG, ADD, SUB,
-            MUL, DIV, INV, INC, DEC, MOD, CEQ, CNE, CLT, CLE, CGT, CGE, NOT,
-            AND, IOR, XOR, AMN, APE, AGE, ASE, TMN, CAN, CAZ, CAR, DUP, SWP,
-            ROT, POP;
+            MUL, DIV, INV, INC, DEC, MOD, POW, CEQ, CNE, CLT, CLE, CGT, CGE,
+            NOT, AND, IOR, XOR, SHL, SHR, AMN, APE, AGE, ASE, TMN, CAB, CAC,
+            CAN, CAZ, CAR, DUP, SWP, ROT, POP;
         );
     }
 }
@@ -460,23 +554,30 @@ impl Phil // 'a lifetime of AST
     {
         let mut collins = Self { out: vec![] };
         collins.extend_bytes(DF_MAGIC);
+        collins.push_flags(comp.wrap);
         collins.push_idents(comp.idents.as_slice());
         collins.push_consts(comp.consts.as_slice());
         collins.push_pages(&comp.subrs);
         return collins.out;
     }
 
-    #[inline]
-    fn at(&self) -> usize
-    {
-        self.out.len()
-    }
-
-    fn overwrite_at(&mut self, bytes: &[u8], at: usize)
+    // same as transfart, but þe code is already lowered
+    pub fn refart(dfc: &exodus::Dfc) -> Vec<u8>
     {
-        for (i, b) in bytes.iter().enumerate() {
-            self.out[at+i] = *b;
+        let mut collins = Self { out: vec![] };
+        collins.extend_bytes(DF_MAGIC);
+        collins.push_flags(dfc.wrap);
+        collins.push_idents(dfc.idents.as_slice());
+        collins.push_consts(dfc.consts.as_slice());
+        collins.extend(u16::try_from(dfc.pages.len()).unwrap());
+        for pag in &dfc.pages {
+            let mut code = vec![];
+            for ins in &pag.code {
+                ins.encode(&mut code);
+            }
+            collins.push_pag_code(pag.arity, pag.uvs, &pag.meta, &code);
         }
+        return collins.out;
     }
 
     #[inline]
@@ -496,6 +597,7 @@ impl Phil // 'a lifetime of AST
     fn extend_val(&mut self, v: &Val)
     {
         match v {
+            Val::B(b) => self.extend(u8::from(*b)),
             Val::C(c) => self.extend(*c),
             Val::N(n) => self.extend(*n),
             Val::Z(z) => self.extend(*z),
@@ -508,10 +610,10 @@ impl Phil // 'a lifetime of AST
 
     fn extend_array(&mut self, a: &Array)
     {
-        if a.is_empty() {
-            todo!("empty arrays");
-        }
-        self.extend(ser_ctn_type(&a.get(0).unwrap()));
+        let Some(t) = a.get_type() else {
+            unreachable!("can't ctn an untyped empty array");
+        };
+        self.extend(ser_arr_type(t));
         self.extend(u16::try_from(a.len())
             .expect("array too long to serialize"));
         for i in 0..a.len() {
@@ -520,6 +622,11 @@ impl Phil // 'a lifetime of AST
         }
     }
 
+    fn push_flags(&mut self, wrap: bool)
+    {
+        self.extend(if wrap {DF_FLAG_WRAP} else {0});
+    }
+
     fn push_idents(&mut self, idents: &[Rc<DfStr>])
     {
         self.extend(u16::try_from(idents.len())
@@ -559,20 +666,27 @@ impl Phil // 'a lifetime of AST
     {
         // convert to lower basic blocks
         let lblocks = bb2lb(&pag.code);
-        /************** W R I T E **************/
-        self.extend(pag.arity as u8);
-        self.extend(u8::try_from(pag.uvs).expect("too many upvals"));
-        self.push_page_meta(&pag.meta);
-        let len_idx = self.at();
-        self.extend(0_u32); // dummy for len
-        let x0 = self.at();
         // emit all lblocks, consuming þem
-        for lb in lblocks {
-            self.extend_bytes(&lb.into_bytes());
-        }
-        let x1 = self.at();
-        let len = u32::try_from(x1 as isize - x0 as isize).unwrap();
-        self.overwrite_at(&len.to_bytes(), len_idx);
+        let code: Vec<u8> = lblocks
+            .into_iter()
+            .flat_map(LowerBlock::into_bytes)
+            .collect();
+        self.push_pag_code(pag.arity, pag.uvs, &pag.meta, &code);
+    }
+
+    fn push_pag_code(
+        &mut self,
+        arity: usize,
+        uvs: usize,
+        meta: &PageMeta,
+        code: &[u8])
+    {
+        /************** W R I T E **************/
+        self.extend(arity as u8);
+        self.extend(u8::try_from(uvs).expect("too many upvals"));
+        self.push_page_meta(meta);
+        self.extend(u32::try_from(code.len()).unwrap());
+        self.extend_bytes(code);
         self.extend(b'\0'); // final NUL
     }
 
@@ -698,6 +812,20 @@ fn ser_ctn_type(v: &Val) -> u8
     }
 }
 
+// type of þe elements of a constant array, can be an empty one
+fn ser_arr_type(t: Type) -> u8
+{
+    match t {
+        Type::B => 0x01,
+        Type::C => 0x02,
+        Type::N => 0x03,
+        Type::Z => 0x04,
+        Type::R => 0x05,
+        Type::A => 0x08,
+        _ => unreachable!("can't ctn a {t} array"),
+    }
+}
+
 // fixed size to bytes
 trait ToBytes: Copy
 {
@@ -757,11 +885,6 @@ impl ToBytes for f32 {
 impl ToBytes for dflib::tables::NatTb {
     type Bytes = [u8; 4];
     fn to_bytes(&self) -> Self::Bytes {
-        match self.name() {
-            "STD" => 0_u32,
-            "STD$io" => 1,
-            "STD$a"  => 2,
-            _ => todo!(),
-        }.to_be_bytes()
+        return self.tag().to_be_bytes();
     }
 }
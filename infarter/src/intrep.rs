@@ -30,6 +30,7 @@ pub enum ImOp
     INC,
     DEC,
     MOD,
+    POW,
 
     CEQ,
     CNE,
@@ -42,6 +43,8 @@ pub enum ImOp
     AND,
     IOR,
     XOR,
+    SHL,
+    SHR,
 
     LGX(DfStrIdx),
     SGX(DfStrIdx),
@@ -66,6 +69,8 @@ pub enum ImOp
 
     LUV(UpvIdx), // Load UpValue (from current norris)
 
+    CAB,
+    CAC,
     CAN,
     CAZ,
     CAR,
@@ -92,6 +97,46 @@ impl ImOp
         }
     }
 
+    // þe predefined L op for `v`, if þere's one
+    pub fn load_const(v: &Val) -> Option<Self>
+    {
+        let op = match v {
+            Val::V => ImOp::LVV,
+            Val::B(b) => ImOp::LBX(*b),
+            Val::N(0) => ImOp::LN0,
+            Val::N(1) => ImOp::LN1,
+            Val::N(2) => ImOp::LN2,
+            Val::N(3) => ImOp::LN3,
+            Val::Z(-1) => ImOp::LM1,
+            Val::Z(0) => ImOp::LZ0,
+            Val::Z(1) => ImOp::LZ1,
+            Val::Z(2) => ImOp::LZ2,
+            _ => return None,
+        };
+        return Some(op);
+    }
+
+    // þe N% or Z% loaded by þis op, for constant folding
+    pub fn int_const(&self, consts: &ArraySet<Val>) -> Option<Val>
+    {
+        let v = match self {
+            ImOp::LN0 => Val::N(0),
+            ImOp::LN1 => Val::N(1),
+            ImOp::LN2 => Val::N(2),
+            ImOp::LN3 => Val::N(3),
+            ImOp::LM1 => Val::Z(-1),
+            ImOp::LZ0 => Val::Z(0),
+            ImOp::LZ1 => Val::Z(1),
+            ImOp::LZ2 => Val::Z(2),
+            ImOp::LKX(i) => match &consts.as_slice()[*i] {
+                v @ (Val::N(_) | Val::Z(_)) => v.clone(),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        return Some(v);
+    }
+
     pub fn is_tbl(&self) -> bool
     {
         matches!(self, ImOp::TGF(_) | ImOp::TSF(_))
@@ -234,6 +279,7 @@ pub struct SubrEnv // subroutine environment compiler
                                     // þe indices are each loop's start
     pub brk:      Stack<Vec<BbIdx>>,// þis one is a stack for each loop
                                     // all þe blocks wiþ terms to be patched
+    pub lpsz:     Stack<usize>,     // locsize at þe start of each loop
 }
 
 impl SubrEnv
@@ -247,11 +293,17 @@ impl SubrEnv
     pub fn exit_scope(&mut self)
     {
         assert!(self.scpdpt != 0);
-        for _ in self.presize..self.locals.size() {
+        for _ in self.presize..self.locsize {
             self.push_op(ImOp::POP);
         }
         self.scpdpt -= 1;
-        self.locals.trunc(self.presize);
+        self.locsize = self.presize;
+        // locals are declared in stack order
+        let outer = self.locals.as_slice()
+            .iter()
+            .take_while(|(_, i)| *i < self.presize)
+            .count();
+        self.locals.trunc(outer);
     }
 
     #[inline]
@@ -308,11 +360,13 @@ impl SubrEnv
     {
         self.agn.push(start_bbi);
         self.brk.push(vec![]);
+        self.lpsz.push(self.locsize);
     }
 
     pub fn end_loop(&mut self, end_bbi: BbIdx)
     {
         self.agn.pop();
+        self.lpsz.pop();
         let patches = self.brk.pop().unwrap();
         let jj = Term::JJX(end_bbi);
         for p in patches {
@@ -344,6 +398,7 @@ pub struct Compiler
     pub idents:  ArraySet<Rc<DfStr>>, // identifier pool
     pub subrs:   Vec<Page>,
     pub curr:    SubrEnv,
+    pub wrap:    bool,                // wrapping integer ariþmetic
 }
 
 impl Compiler
@@ -441,9 +496,12 @@ impl Compiler
             BinOpcode::Mul => ImOp::MUL,
             BinOpcode::Div => ImOp::DIV,
             BinOpcode::Mod => ImOp::MOD,
+            BinOpcode::Pow => ImOp::POW,
             BinOpcode::And => ImOp::AND,
             BinOpcode::Or  => ImOp::IOR,
             BinOpcode::Xor => ImOp::XOR,
+            BinOpcode::Shl => ImOp::SHL,
+            BinOpcode::Shr => ImOp::SHR,
             BinOpcode::Eq  => ImOp::CEQ,
             BinOpcode::Ne  => ImOp::CNE,
             BinOpcode::Lt  => ImOp::CLT,
@@ -510,49 +568,50 @@ impl Compiler
 
     fn stmt(&mut self, s: &Stmt)
     {
-        match s {
-            Stmt::Assign(v, e)    => self.s_assign(v, e),
-            Stmt::OperOn(l, o, e) => self.s_operon(l, o, e),
-            Stmt::IfElse(i, o, e) => self.s_ifelse(i, o, e),
-            Stmt::Switch(m, c, d) => self.s_switch(m, c, d),
-            Stmt::LoopIf(l)       => self.s_loopif(l),
-            Stmt::PcCall(p, a)    => self.s_pccall(p, a),
-            Stmt::TbPCal(t, f, a) => self.obj_call(t, f, a, SubrType::P),
-            Stmt::PcExit          => {self.term_curr_bb(Term::END);},
-            Stmt::Return(e)       => self.s_return(e),
-            Stmt::AgainL(l)       => self.s_againl(*l),
-            Stmt::BreakL(l)       => self.s_breakl(*l),
+        match &s.kind {
+            StmtKind::Assign(v, e)    => self.s_assign(v, e),
+            StmtKind::OperOn(l, o, e) => self.s_operon(l, o, e),
+            StmtKind::IfElse(i, o, e) => self.s_ifelse(i, o, e),
+            StmtKind::Switch(m, c, d) => self.s_switch(m, c, d),
+            StmtKind::LoopIf(l)       => self.s_loopif(l),
+            StmtKind::PcCall(p, a)    => self.s_pccall(p, a),
+            StmtKind::TbPCal(t, f, a) => self.obj_call(t, f, a, SubrType::P),
+            StmtKind::PcExit          => {self.term_curr_bb(Term::END);},
+            StmtKind::Return(e)       => self.s_return(e),
+            StmtKind::AgainL(l)       => self.s_againl(*l),
+            StmtKind::BreakL(l)       => self.s_breakl(*l),
         }
     }
 
     fn s_assign(&mut self, v: &Expr, ex: &Expr)
     {
-        match v {
-            Expr::Ident(s) => self.s_varass(s, ex),
-            Expr::BinOp(a, BinOpcode::Idx, i) =>
+        match &v.kind {
+            ExprKind::Ident(s) => self.s_varass(s, ex),
+            ExprKind::BinOp(a, BinOpcode::Idx, i) =>
                 return self.s_arrass(a, i, ex),
-            Expr::TblFd(t, f) =>
+            ExprKind::TblFd(t, f) =>
                 return self.s_tblass(t, f, ex),
-            _ => panic!("cannot assign to {v:?}"),
+            _ => panic!("cannot assign to {:?} at {}", v.kind, v.span),
         }
     }
 
     #[inline]
     fn s_operon(&mut self, lhs: &Expr, op: &BinOpcode, ex: &Expr)
     {
-        match lhs {
-            Expr::Ident(s) => { // s = s op ex., only for locals, not upvals
+        match &lhs.kind {
+            ExprKind::Ident(s) => { // s = s op ex., only for locals, not upvals
                 self.e_binop(lhs, op, ex);
                 // find local
                 let Some(idx) = self.resolve_local(s) else {
-                    panic!("operon on identifiers only works for locals");
+                    panic!("operon on identifiers only works for locals, \
+                        not {s} at {}", lhs.span);
                 };
                 self.push_op(ImOp::SLX(*idx));
             },
-            Expr::BinOp(a, BinOpcode::Idx, i) =>
+            ExprKind::BinOp(a, BinOpcode::Idx, i) =>
                 self.s_operon_arr(a, i, op, ex),
-            Expr::TblFd(t, f) => self.s_operon_tbl(t, f, op, ex),
-            _ => panic!("cannot operon to {lhs:?}"),
+            ExprKind::TblFd(t, f) => self.s_operon_tbl(t, f, op, ex),
+            _ => panic!("cannot operon to {:?} at {}", lhs.kind, lhs.span),
         }
     }
 
@@ -684,6 +743,7 @@ impl Compiler
             self.block(def);
             return;
         }
+        self.incloc(); // þe matchee is on þe stack during þe cases
         // code following, very similar to s_ifstmt
         // do þe cases
         let mut last_case_idx;
@@ -694,8 +754,8 @@ impl Compiler
         last_case_idx = self.term_curr_bb(Term::PCH(true)); // JNX
         self.block(&cas[0].blok);
         jjx_idxs.push(self.term_curr_bb(Term::PCH(false))); // JJX
-        // oþer if cases
-        for c in cas {
+        // oþer cases
+        for c in &cas[1..] {
             self.curr.patch_jump(
                 last_case_idx, Term::JNX(self.curr_idx()));
             // DUP (mat mat)
@@ -709,11 +769,12 @@ impl Compiler
             self.block(&c.blok);
             jjx_idxs.push(self.term_curr_bb(Term::PCH(false)));
         }
+        // join last case to default, which starts here (þe default may
+        // be many bbs or end in a RET, so don't use its last bb)
+        self.curr.patch_jump(last_case_idx, Term::JNX(self.curr_idx()));
         // default case, even if mt, it will be optimized away
         self.block(def);
-        let last_patch = self.term_curr_bb(Term::NOP);
-        // join last case to default
-        self.curr.patch_jump(last_case_idx, Term::JNX(last_patch));
+        self.term_curr_bb(Term::NOP);
         // patch all jjx's
         let end = self.curr_idx();
         for i in jjx_idxs {
@@ -721,16 +782,19 @@ impl Compiler
         }
         // POP þe matchee
         self.push_op(ImOp::POP);
+        self.decloc();
     }
 
     fn s_loopif(&mut self, lo: &Loop)
     {
+        let presize = self.locsize();
         self.curr.enter_scope();
         self.lvv_loop(lo);
         match lo {
             Loop::Inf(b)       => self.s_inf_loop(b),
             Loop::Cdt(p, e, b) => self.s_cdt_loop(p, e, b),
         }
+        self.curr.presize = presize;
         self.curr.exit_scope();
     }
 
@@ -752,10 +816,11 @@ impl Compiler
     fn lvv_in_block(&mut self, block: &Block)
     {
         for s in block {
-            if let Stmt::Assign(v, _) = s {
-                if let Expr::Ident(i) = v {
+            if let StmtKind::Assign(v, _) = &s.kind {
+                if let ExprKind::Ident(i) = &v.kind {
                     if !self.exists_var(i) { // is new locar var
-                        self.s_assign(v, &Expr::Const(Val::V));
+                        let vv = Expr::new(ExprKind::Const(Val::V), v.span);
+                        self.s_assign(v, &vv);
                     }
                 }
             }
@@ -822,65 +887,68 @@ impl Compiler
 
     fn s_againl(&mut self, lev: u32)
     {
-        let loop_start = self.curr.agn.peek(lev as usize)
+        let loop_start = *self.curr.agn.peek(lev as usize)
             .expect("@@ too deep, þer'r no so many levels");
-        self.term_curr_bb(Term::JJX(*loop_start));
+        self.pop_to_loop(lev);
+        self.term_curr_bb(Term::JJX(loop_start));
     }
 
     fn s_breakl(&mut self, lev: u32)
     {
-        let here = self.curr_idx();
+        self.pop_to_loop(lev);
+        let here = self.term_curr_bb(Term::PCH(false));
         self.curr.brk.peek_mut(lev as usize)
             .expect(".@ too deep, þer'r no so many levels")
             .push(here);
     }
 
+    // before jumping out of inner scopes, leave þe stack as it was
+    // at þe start of þe loop
+    fn pop_to_loop(&mut self, lev: u32)
+    {
+        let Some(&lpsz) = self.curr.lpsz.peek(lev as usize) else {
+            return; // will panic later
+        };
+        for _ in lpsz..self.locsize() {
+            self.push_op(ImOp::POP);
+        }
+    }
+
     fn expr(&mut self, ex: &Expr)
     {
-        match ex {
-            Expr::Const(v)       => self.e_const(v),
-            Expr::Ident(i)       => self.e_ident(i),
-            Expr::Tcast(t, e)    => self.e_tcast(t, e),
-            Expr::UniOp(e, o)    => self.e_uniop(e, o),
-            Expr::BinOp(l, o, r) => self.e_binop(l, o, r),
-            Expr::CmpOp(l, v)    => self.e_cmpop(l, v),
-            Expr::Array(a)       => self.e_array(a),
-            Expr::Table(v)       => self.e_table(v),
-            Expr::TblFd(t, f)    => self.e_tblfd(t, f),
-            Expr::RecsT(l)       => self.e_recst(*l),
-            Expr::FnDef(s)       => self.e_fndef(&s.borrow()),
-            Expr::Fcall(f, a)    => self.e_fcall(f, a),
-            Expr::TbFcl(t, f, a) => self.obj_call(t, f, a, SubrType::F),
-            Expr::PcDef(s)       => self.e_pcdef(&s.borrow()),
-            Expr::RecFn |
-            Expr::RecPc => self.push_op(ImOp::LLX(0)), // unchecked
-            Expr::IfExp(c, e)    => self.e_ifexp(c, e),
+        match &ex.kind {
+            ExprKind::Const(v)       => self.e_const(v),
+            ExprKind::Ident(i)       => self.e_ident(i, ex.span),
+            ExprKind::Tcast(t, e)    => self.e_tcast(t, e),
+            ExprKind::UniOp(e, o)    => self.e_uniop(e, o),
+            ExprKind::BinOp(l, o, r) => self.e_binop(l, o, r),
+            ExprKind::CmpOp(l, v)    => self.e_cmpop(l, v),
+            ExprKind::Array(a)       => self.e_array(a),
+            ExprKind::Table(v)       => self.e_table(v),
+            ExprKind::TblFd(t, f)    => self.e_tblfd(t, f),
+            ExprKind::RecsT(l)       => self.e_recst(*l),
+            ExprKind::FnDef(s)       => self.e_fndef(&s.borrow(), ex.span),
+            ExprKind::Fcall(f, a)    => self.e_fcall(f, a),
+            ExprKind::TbFcl(t, f, a) => self.obj_call(t, f, a, SubrType::F),
+            ExprKind::PcDef(s)       => self.e_pcdef(&s.borrow(), ex.span),
+            ExprKind::RecFn |
+            ExprKind::RecPc => self.push_op(ImOp::LLX(0)), // unchecked
+            ExprKind::IfExp(c, e)    => self.e_ifexp(c, e),
         }
     }
 
     // þis checks predefined consts
     fn e_const(&mut self, v: &Val)
     {
+        if let Some(op) = ImOp::load_const(v) {
+            return self.push_op(op);
+        }
         match v {
-            Val::V => return self.push_op(ImOp::LVV),
-            Val::B(b) => return self.push_op(ImOp::LBX(*b)),
-            Val::N(n) => match n {
-                0 => return self.push_op(ImOp::LN0),
-                1 => return self.push_op(ImOp::LN1),
-                2 => return self.push_op(ImOp::LN2),
-                3 => return self.push_op(ImOp::LN3),
-                _ => {},
-            },
-            Val::Z(z) => match z {
-                -1 => return self.push_op(ImOp::LM1),
-                0 => return self.push_op(ImOp::LZ0),
-                1 => return self.push_op(ImOp::LZ1),
-                2 => return self.push_op(ImOp::LZ2),
-                _ => {},
-            },
             // oþers must be internalized
             Val::C(_) |
             Val::R(_) |
+            Val::N(_) |
+            Val::Z(_) |
             Val::T(Table::Nat(_)) |
             Val::A(_) => {},
             _ => todo!("oþer consts {:?}", v),
@@ -895,7 +963,7 @@ impl Compiler
         self.push_op(ImOp::LKX(idx));
     }
 
-    fn e_ident(&mut self, id: &Rc<DfStr>)
+    fn e_ident(&mut self, id: &Rc<DfStr>, span: Span)
     {
         if id.as_bytes() == b"STD" {
             todo!("STD");
@@ -908,17 +976,19 @@ impl Compiler
             self.push_op(ImOp::LUV(i));
             return;
         }
-        panic!("could not resolve symbol {id}");
+        panic!("could not resolve symbol {id} at {span}");
     }
 
     fn e_tcast(&mut self, t: &Type, e: &Expr)
     {
         self.expr(e);
         match t {
+            Type::B => self.push_op(ImOp::CAB),
+            Type::C => self.push_op(ImOp::CAC),
+            Type::N => self.push_op(ImOp::CAN),
             Type::Z => self.push_op(ImOp::CAZ),
             Type::R => self.push_op(ImOp::CAR),
-            Type::N => self.push_op(ImOp::CAN),
-            _ => todo!(),
+            _ => unreachable!(), // only [BCNZR]% casts are parsed
         }
     }
 
@@ -957,15 +1027,47 @@ impl Compiler
 
     fn e_cmpop(&mut self, l: &Expr, v: &[(BinOpcode, Expr)])
     {
+        /*
+        **  `a < b <= c` is `a < b &? b <= c`, but `b` is eval'd once
+        **      [a] [b]     (a b)
+        **      DUP ROT     (b a b)
+        **      LT          (b a<b)
+        **      JBF fail
+        **      POP         (b)
+        **      [c] LE      (b<=c)
+        **      JJX end
+        **  fail:
+        **      SWP POP     (F)
+        **  end:
+        */
         self.expr(l);
-        match v.len() {
-            0 => return,
-            1 => { // normal cmpop
-                self.expr(&v[0].1);
-                self.push_binop(&v[0].0);
-            },
-            _ => todo!("multi cmpop"),
+        let Some(((last_op, last), init)) = v.split_last() else {
+            return;
+        };
+        let mut jbf_idxs = vec![];
+        for (op, e) in init {
+            self.expr(e);
+            self.push_op(ImOp::DUP);
+            self.push_op(ImOp::ROT);
+            self.push_binop(op);
+            jbf_idxs.push(self.term_curr_bb(Term::PCH(true)));
+            self.push_op(ImOp::POP);
+        }
+        self.expr(last);
+        self.push_binop(last_op);
+        if jbf_idxs.is_empty() { // normal cmpop
+            return;
         }
+        let jjx_idx = self.term_curr_bb(Term::PCH(false));
+        let fail = self.curr_idx();
+        for i in jbf_idxs {
+            self.curr.patch_jump(i, Term::JBF(fail));
+        }
+        self.push_op(ImOp::SWP);
+        self.push_op(ImOp::POP);
+        self.term_curr_bb(Term::NOP);
+        let end = self.curr_idx();
+        self.curr.patch_jump(jjx_idx, Term::JJX(end));
     }
 
     fn e_array(&mut self, a: &[Expr])
@@ -1006,20 +1108,20 @@ impl Compiler
         self.push_op(ImOp::LLX(*loc));
     }
 
-    pub fn e_fndef(&mut self, subr: &Subr)
+    pub fn e_fndef(&mut self, subr: &Subr, span: Span)
     {
         let pagidx = self.comp_subr(subr, SubrType::F);
         for id in &subr.upvs {
-            self.e_ident(id);
+            self.e_ident(id, span);
         }
         self.push_op(ImOp::FMN(pagidx));
     }
 
-    pub fn e_pcdef(&mut self, subr: &Subr)
+    pub fn e_pcdef(&mut self, subr: &Subr, span: Span)
     {
         let pagidx = self.comp_subr(subr, SubrType::P);
         for id in &subr.upvs {
-            self.e_ident(id);
+            self.e_ident(id, span);
         }
         self.push_op(ImOp::PMN(pagidx));
     }
@@ -1053,9 +1155,11 @@ impl Compiler
             self.curr.patch_jump(last_if_idx, Term::JFX(self.curr_idx()));
             if_case!(self, last_if_idx, jjx_idxs, c);
         }
+        // þe default starts here, it may span many blocks
+        let elze_idx = self.curr_idx();
         self.expr(elze);
-        let last_patch = self.term_curr_bb(Term::NOP);
-        self.curr.patch_jump(last_if_idx, Term::JFX(last_patch));
+        self.term_curr_bb(Term::NOP);
+        self.curr.patch_jump(last_if_idx, Term::JFX(elze_idx));
         let end = self.curr_idx();
         for i in jjx_idxs {
             self.curr.patch_jump(i, Term::JJX(end));
@@ -1130,6 +1234,50 @@ impl Default for Compiler
             idents: ArraySet::default(),
             subrs:  vec![],
             curr: SubrEnv::default(),
+            wrap: false,
+        }
+    }
+}
+
+// human readable dump of þe IR, for --emit=ir
+impl std::fmt::Display for Compiler
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        if self.wrap {
+            writeln!(f, "wrapping ariþmetic")?;
+        }
+        writeln!(f, "idents:")?;
+        for (i, id) in self.idents.iter().enumerate() {
+            writeln!(f, "  {i:4} {id}")?;
+        }
+        writeln!(f, "consts:")?;
+        for (i, k) in self.consts.iter().enumerate() {
+            writeln!(f, "  {i:4} {k} ({})", Type::from(k))?;
+        }
+        for (i, pag) in self.subrs.iter().enumerate() {
+            write!(f, "page {i}")?;
+            if let Some(n) = pag.meta.name {
+                write!(f, " \"{}\"", self.idents.as_slice()[n])?;
+            }
+            writeln!(f, ": {}-ary, {} upvals, line {}",
+                pag.arity, pag.uvs, pag.meta.line)?;
+            for (j, bb) in pag.code.iter().enumerate() {
+                write!(f, "  bb{j}")?;
+                if !bb.pred.is_empty() {
+                    let preds = bb.pred
+                        .iter()
+                        .map(|p| format!("bb{p}"))
+                        .collect::<Vec<_>>();
+                    write!(f, " <- {}", preds.join(", "))?;
+                }
+                writeln!(f, ":")?;
+                for op in &bb.code {
+                    writeln!(f, "    {op:?}")?;
+                }
+                writeln!(f, "    {:?}", bb.term)?;
+            }
         }
+        return Ok(());
     }
 }
@@ -0,0 +1,231 @@
+/* leviticus.rs */
+
+// þe law after exodus: static checks over þe pages of a .dfc, so þat a bad
+// file is rejected here instead of crashing FlatVM at runtime
+
+use crate::{
+    genesis::{Op, OpArg},
+    exodus::{Dfc, DfcPage, Instr},
+    util::{self, StrRes},
+};
+
+pub fn verify_bytes(bytes: &[u8]) -> StrRes<()>
+{
+    return verify(&Dfc::from_bytes(bytes)?);
+}
+
+// all errors found, one per line
+pub fn verify(dfc: &Dfc) -> StrRes<()>
+{
+    let mut errs = vec![];
+    for (pi, pag) in dfc.pages.iter().enumerate() {
+        let mut law = Law { dfc: dfc, pag: pag, errs: vec![] };
+        law.check_pools();
+        law.check_jumps();
+        // stack depþs are meaningless if þe code is not well formed
+        if law.errs.is_empty() {
+            law.check_stack(if pi == 0 {0} else {1 + pag.arity});
+        }
+        errs.extend(law.errs
+            .into_iter()
+            .map(|e| format!("VerifyError: {e} in page {pi}")));
+    }
+    if errs.is_empty() {
+        return Ok(());
+    }
+    return util::format_err!("{}", errs.join("\n"));
+}
+
+struct Law<'a>
+{
+    dfc:  &'a Dfc,
+    pag:  &'a DfcPage,
+    errs: Vec<String>,
+}
+
+impl Law<'_>
+{
+    fn check_pools(&mut self)
+    {
+        if let Some(n) = self.pag.meta.name {
+            if n >= self.dfc.idents.len() {
+                self.errs.push(format!("name identifier {n} out of range"));
+            }
+        }
+        for ins in &self.pag.code {
+            let (what, len) = match ins.op.arg() {
+                OpArg::Ctn(_) => ("constant",   self.dfc.consts.len()),
+                OpArg::Idf(_) => ("identifier", self.dfc.idents.len()),
+                OpArg::Pag(_) => ("page",       self.dfc.pages.len()),
+                _ => continue,
+            };
+            if ins.arg as usize >= len {
+                self.errs.push(format!("{:?} at {:04}: {what} {} out of \
+                    range (pool has {len})", ins.op, ins.at, ins.arg));
+            }
+        }
+    }
+
+    fn check_jumps(&mut self)
+    {
+        for ins in &self.pag.code {
+            let Some(tgt) = ins.jmp_target() else {
+                continue;
+            };
+            if self.index_at(tgt).is_none() {
+                self.errs.push(format!("{:?} at {:04}: target {tgt:04} \
+                    is not an instruction", ins.op, ins.at));
+            }
+        }
+    }
+
+    // abstract interpretation of þe stack depþ, wiþ `d0` at þe start
+    fn check_stack(&mut self, d0: usize)
+    {
+        let code = &self.pag.code;
+        if code.is_empty() {
+            self.errs.push("empty code".into());
+            return;
+        }
+        let mut depth: Vec<Option<usize>> = vec![None; code.len()];
+        let mut todo = vec![(0, d0)];
+        while let Some((i, d)) = todo.pop() {
+            match depth[i] {
+                Some(e) if e == d => continue,
+                Some(e) => {
+                    self.errs.push(format!("{:?} at {:04}: stack depþ is \
+                        eiþer {e} or {d}", code[i].op, code[i].at));
+                    return;
+                },
+                None => depth[i] = Some(d),
+            }
+            let ins = &code[i];
+            let (pops, pushes) = self.stack_eff(ins);
+            if d < pops {
+                self.errs.push(format!("{:?} at {:04}: pops {pops} values \
+                    but stack depþ is {d}", ins.op, ins.at));
+                return;
+            }
+            let nd = d - pops + pushes;
+            if let Some(tgt) = ins.jmp_target() {
+                todo.push((self.index_at(tgt).unwrap(), nd));
+            }
+            if falls_through(ins.op) {
+                if i + 1 == code.len() {
+                    self.errs.push(format!("{:?} at {:04}: falls off þe \
+                        end of þe page", ins.op, ins.at));
+                    return;
+                }
+                todo.push((i + 1, nd));
+            }
+        }
+    }
+
+    // (# of values needed & popped, # of values pushed)
+    fn stack_eff(&self, ins: &Instr) -> (usize, usize)
+    {
+        match ins.op {
+            Op::NOP |
+            Op::JJS |
+            Op::JJL |
+            Op::END |
+            Op::HLT => (0, 0),
+            Op::LVV |
+            Op::LBT |
+            Op::LBF |
+            Op::LN0 |
+            Op::LN1 |
+            Op::LN2 |
+            Op::LN3 |
+            Op::LM1 |
+            Op::LZ0 |
+            Op::LZ1 |
+            Op::LZ2 |
+            Op::LR0 |
+            Op::LR1 |
+            Op::LKS |
+            Op::LKL |
+            Op::LGL |
+            Op::LLS |
+            Op::LLL |
+            Op::LUV |
+            Op::AMN |
+            Op::TMN => (0, 1),
+            Op::NEG |
+            Op::INV |
+            Op::INC |
+            Op::DEC |
+            Op::NOT |
+            Op::CAB |
+            Op::CAC |
+            Op::CAN |
+            Op::CAZ |
+            Op::CAR |
+            Op::ULS |
+            Op::ULL |
+            Op::JBT |
+            Op::JBF |
+            Op::TGF |
+            Op::RET => (1, 1),
+            Op::SGL |
+            Op::SLS |
+            Op::SLL |
+            Op::JTS |
+            Op::JTL |
+            Op::JFS |
+            Op::JFL |
+            Op::POP => (1, 0),
+            Op::DUP => (1, 2),
+            Op::ADD |
+            Op::SUB |
+            Op::MUL |
+            Op::DIV |
+            Op::MOD |
+            Op::POW |
+            Op::CEQ |
+            Op::CNE |
+            Op::CLT |
+            Op::CLE |
+            Op::CGT |
+            Op::CGE |
+            Op::AND |
+            Op::IOR |
+            Op::XOR |
+            Op::SHL |
+            Op::SHR |
+            Op::APE |
+            Op::AGE |
+            Op::TSF => (2, 1),
+            Op::SWP => (2, 2),
+            Op::JES |
+            Op::JEL |
+            Op::JNS |
+            Op::JNL |
+            Op::JLT |
+            Op::JLE |
+            Op::JGT |
+            Op::JGE => (2, 0),
+            Op::ROT => (3, 3),
+            Op::ASE => (3, 0),
+            // upvalues are popped into þe new subroutine
+            Op::PMN |
+            Op::FMN => (self.dfc.pages[ins.arg as usize].uvs, 1),
+            // args + callee, after returning
+            Op::PCL => (ins.arg as usize + 1, 0),
+            Op::FCL => (ins.arg as usize + 1, 1),
+        }
+    }
+
+    // index in self.pag.code of þe instruction at byte offset `at`
+    fn index_at(&self, at: isize) -> Option<usize>
+    {
+        let at = usize::try_from(at).ok()?;
+        return self.pag.code.binary_search_by_key(&at, |ins| ins.at).ok();
+    }
+}
+
+fn falls_through(op: Op) -> bool
+{
+    !matches!(op, Op::JJS | Op::JJL | Op::RET | Op::END | Op::HLT)
+}
+
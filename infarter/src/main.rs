@@ -1,7 +1,13 @@
 /* main.rs */
 
-use std::io::Write;
+use std::io::{Read, Write};
+use std::process::ExitCode;
 
+pub mod cli;
+pub mod disasm;
+pub mod exodus;
+pub mod leviticus;
+pub mod repl;
 pub mod parsnip;
 pub mod asterix;
 pub mod tarzan;
@@ -12,63 +18,139 @@ pub mod optimus;
 pub mod genesis;
 pub mod util;
 
-fn main()
+use cli::{Args, Cmd, Emit};
+
+fn main() -> ExitCode
 {
-    let argv: Vec<_> = std::env::args().collect();
-    let argc: usize = argv.len();
-    if argc == 2 {
-        parse_file(&argv[1]);
-    } else if argc == 3 {
-        match argv[1].as_str() {
-            "t"  => transfart(&argv[2], false),
-            "to" => transfart(&argv[2], true),
-            _ => panic!("unknown option {}", argv[1]),
-        }
-    } else {
-        eprintln!("not rite numba ({argc}) of args, must be 2, {}",
-            argv[0]);
+    let cmdline: Vec<_> = std::env::args().skip(1).collect();
+    let args = match Args::parse(&cmdline) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        },
+    };
+    let res = match args.cmd {
+        Cmd::Run     => parse_file(&args),
+        Cmd::Check   => check_file(&args),
+        Cmd::Compile => transfart(&args),
+        Cmd::Dis     => disasm_file(&args),
+        Cmd::Verify  => verify_file(&args),
+        Cmd::Repl    => repl::repl(),
+    };
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+pub fn read_input(args: &Args) -> Result<String, String>
+{
+    if args.from_stdin() {
+        let mut taco = String::new();
+        return match std::io::stdin().read_to_string(&mut taco) {
+            Ok(_) => Ok(taco),
+            Err(e) => util::format_err!("Could not read stdin: {e}"),
+        };
     }
+    return std::fs::read_to_string(&args.input)
+        .map_err(|e| format!("Could not read {}: {e}", args.input));
 }
 
-pub fn parse_file(fname: &str)
+fn read_input_bytes(args: &Args) -> Result<Vec<u8>, String>
 {
-    let taco: String = read_file_to_string(fname);
-    let mut ast = match parsnip::parse(taco) {
-        Ok(b) => b,
-        Err(e) => {eprintln!("{e}"); return;},
+    let mut bytes = vec![];
+    let res = if args.from_stdin() {
+        std::io::stdin().read_to_end(&mut bytes)
+    } else {
+        std::fs::File::open(&args.input)
+            .and_then(|mut f| f.read_to_end(&mut bytes))
+    };
+    return match res {
+        Ok(_) => Ok(bytes),
+        Err(e) => util::format_err!("Could not read {}: {e}", args.input),
     };
+}
+
+fn parse_and_check(args: &Args) -> Result<asterix::Block, String>
+{
+    let taco: String = read_input(args)?;
+    let fname = if args.from_stdin() {"<stdin>"} else {&args.input};
+    let mut ast = parsnip::parse(taco.clone())
+        .map_err(|e| parsnip::render_all(&e, &taco, fname))?;
     semanal::check(&mut ast);
-    tarzan::exec_main(&ast);
+    return Ok(ast);
+}
+
+pub fn parse_file(args: &Args) -> Result<(), String>
+{
+    let ast = parse_and_check(args)?;
+    return tarzan::exec_main(&ast, args.wrap);
 }
 
-#[inline]
-pub fn read_file_to_string(fname: &str) -> String
+pub fn check_file(args: &Args) -> Result<(), String>
 {
-    return std::fs::read_to_string(fname)
-            .expect("Should have been able to read the file");
+    parse_and_check(args)?;
+    return Ok(());
 }
 
-pub fn transfart(ifname: &str, opt: bool)
+pub fn transfart(args: &Args) -> Result<(), String>
 {
-    let taco: String = read_file_to_string(ifname);
-    let mut ofname: String = ifname.to_owned();
-    ofname.push('c');
-    let mut ast = match parsnip::parse(taco) {
-        Ok(b) => b,
-        Err(e) => {eprintln!("{e}"); return;},
+    let out: Vec<u8> = match args.emit {
+        Emit::Tokens => parsnip::dump_tokens(read_input(args)?)?.into(),
+        Emit::Ast => format!("{:#?}\n", parse_and_check(args)?).into(),
+        Emit::Ir => compile(args)?.to_string().into(),
+        Emit::Bytecode => genesis::comp_into_bytes(&compile(args)?),
+    };
+    let Some(ofname) = args.output_path() else {
+        return std::io::stdout()
+            .write_all(&out)
+            .map_err(|e| format!("Could not write to stdout: {e}"));
     };
-    semanal::check(&mut ast);
-    let mut cfg = intrep::Compiler::from_asterix(&ast);
-    if opt {
-        optimus::opt_bblocks(&mut cfg);
-    }
     let mut ofile = std::fs::File::create(&ofname)
-        .expect("could not create file");
-    match ofile.write_all(&genesis::comp_into_bytes(&cfg)) {
-        Ok(()) => println!("Successfully transfarted{} to {}",
-            if opt {" optimized"} else {""},
-            ofname,
-        ),
-        Err(e) => eprintln!("Could not write to binary file because:\n {e}"),
+        .map_err(|e| format!("Could not create {ofname}: {e}"))?;
+    match ofile.write_all(&out) {
+        Ok(()) => if args.emit == Emit::Bytecode {
+            println!("Successfully transfarted{} to {}",
+                if args.opt != 0 {" optimized"} else {""},
+                ofname,
+            );
+        },
+        Err(e) => return util::format_err!(
+            "Could not write to binary file because:\n {e}"),
+    }
+    return Ok(());
+}
+
+pub fn disasm_file(args: &Args) -> Result<(), String>
+{
+    let bytes = read_input_bytes(args)?;
+    if args.roundtrip {
+        disasm::roundtrip(&bytes)
+            .map_err(|e| format!("{}: {e}", args.input))?;
+        println!("{}: round trip OK", args.input);
+        return Ok(());
     }
+    print!("{}", disasm::disasm(&bytes, &args.input)?);
+    return Ok(());
+}
+
+pub fn verify_file(args: &Args) -> Result<(), String>
+{
+    let bytes = read_input_bytes(args)?;
+    leviticus::verify_bytes(&bytes)?;
+    println!("{}: OK", args.input);
+    return Ok(());
+}
+
+fn compile(args: &Args) -> Result<intrep::Compiler, String>
+{
+    let ast = parse_and_check(args)?;
+    let mut cfg = intrep::Compiler::from_asterix(&ast);
+    cfg.wrap = args.wrap;
+    optimus::opt_level(&mut cfg, args.opt);
+    return Ok(cfg);
 }
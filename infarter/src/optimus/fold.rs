@@ -0,0 +1,53 @@
+/* optimus/fold.rs */
+
+use crate::{
+    asterix::{Val, BinOpcode},
+    intrep::*,
+    util::ArraySet,
+};
+
+/*
+**  constant folding of N% & Z% ariþmetic on 1 BasicBlock, wiþ þe same
+**  rules as tarzan & FlatVM: if it would overflow (& not `wrap`) or it's a
+**  modulo by zero, þe op is left as is, so it fails at runtime
+*/
+pub fn fold_bb(bb: &mut BasicBlock, consts: &mut ArraySet<Val>, wrap: bool)
+{
+    let mut i = 0;
+    while i < bb.code.len() {
+        if let Some((start, v)) = fold_at(&bb.code, i, consts, wrap) {
+            let op = ImOp::load_const(&v)
+                .unwrap_or_else(|| ImOp::LKX(consts.add(v)));
+            bb.code.splice(start..=i, [op]);
+            i = start; // þe result may be an operand of þe next op
+        }
+        i += 1;
+    }
+}
+
+// if þe op at `i` can be folded, where its operands start & þe result
+fn fold_at(code: &[ImOp], i: usize, consts: &ArraySet<Val>, wrap: bool)
+ -> Option<(usize, Val)>
+{
+    let binop = match code[i] {
+        ImOp::NEG if i >= 1 => {
+            let a = code[i-1].int_const(consts)?;
+            return a.neg(wrap)?.ok().map(|v| (i-1, v));
+        },
+        ImOp::ADD => BinOpcode::Add,
+        ImOp::SUB => BinOpcode::Sub,
+        ImOp::MUL => BinOpcode::Mul,
+        ImOp::DIV => BinOpcode::Div,
+        ImOp::MOD => BinOpcode::Mod,
+        ImOp::POW => BinOpcode::Pow,
+        ImOp::SHL => BinOpcode::Shl,
+        ImOp::SHR => BinOpcode::Shr,
+        _ => return None,
+    };
+    if i < 2 {
+        return None;
+    }
+    let a = code[i-2].int_const(consts)?;
+    let b = code[i-1].int_const(consts)?;
+    return a.int_arith(binop, &b, wrap)?.ok().map(|v| (i-2, v));
+}
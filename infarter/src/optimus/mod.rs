@@ -1,5 +1,6 @@
 /* optimus/mod.rs */
 
+mod fold; // constant folding
 mod peep; // peephole
 mod term; // BB's terminating op opt
 
@@ -9,9 +10,25 @@ const OPT_PASSES: usize = 5;
 
 pub fn opt_bblocks(comp: &mut Compiler)
 {
-    for _ in 0..OPT_PASSES {
+    opt_passes(comp, OPT_PASSES);
+}
+
+// -O0 does noþing, -O1 a single pass & -O2 all of þem
+pub fn opt_level(comp: &mut Compiler, level: u8)
+{
+    match level {
+        0 => {},
+        1 => opt_passes(comp, 1),
+        _ => opt_bblocks(comp),
+    }
+}
+
+fn opt_passes(comp: &mut Compiler, passes: usize)
+{
+    for _ in 0..passes {
         for pag in &mut comp.subrs {
             for (bbi, bb) in pag.code.iter_mut().enumerate() {
+                fold::fold_bb(bb, &mut comp.consts, comp.wrap);
                 peep::opt_one_bb(bb);
                 term::reduce(bb, bbi);
             }
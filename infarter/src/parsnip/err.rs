@@ -0,0 +1,160 @@
+/* parsnip/err.rs */
+
+use std::fmt::{self, Write};
+use super::toki::TokTyp;
+use crate::asterix::Span;
+
+pub type ParsRes<T> = Result<T, ParsnipError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrKind
+{
+    Expected(Vec<TokTyp>), // any of þese tokens
+    ExpectedExpr,          // þe start of an expression
+    UnknownToken,
+    BadLiteral(String),    // wiþ þe reason
+    Unterminated,          // string or C% literal
+    BadEscape(u8),         // þe char after `?`
+    NotLatin1,             // þe source has chars > U+00FF
+    NoDefault,             // if-expression wiþout þe last value
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsnipError
+{
+    pub kind:  ErrKind,
+    pub found: TokTyp,
+    pub span:  Span, // of þe found token
+}
+
+impl ParsnipError
+{
+    // true if þe source ended too soon, i.e. it miȝt be completed
+    pub fn at_eof(&self) -> bool
+    {
+        match self.kind {
+            // strings can span many lines
+            ErrKind::Unterminated => self.found == TokTyp::String,
+            _ => self.found == TokTyp::Eof,
+        }
+    }
+
+    fn what(&self) -> String
+    {
+        match &self.kind {
+            ErrKind::Expected(v) => format!("expected {}, found {}",
+                one_of(v), self.found),
+            ErrKind::ExpectedExpr =>
+                format!("expected an expression, found {}", self.found),
+            ErrKind::UnknownToken => "unknown token".into(),
+            ErrKind::BadLiteral(why) => format!("bad {}: {why}", self.found),
+            ErrKind::Unterminated => format!("unterminated {}", self.found),
+            ErrKind::BadEscape(c) => format!("unknown escape `?{}` in {}",
+                char::from(*c), self.found),
+            ErrKind::NotLatin1 =>
+                "source contains Unicode chars greater than U+00FF".into(),
+            ErrKind::NoDefault => "if-expression has no default value \
+                (add `=> value` before þe `]`)".into(),
+        }
+    }
+
+    // rustc-like, wiþ þe offending line & a caret underline
+    pub fn render(&self, src: &str, fname: &str) -> String
+    {
+        // latin-1 bytes, so þat þe span offsets match
+        let src: Vec<u8> = src.chars()
+            .map(|c| u8::try_from(c).unwrap_or(b'?'))
+            .collect();
+        let mut res = wrap("ParsnipError: ", &self.what());
+        if self.kind == ErrKind::NotLatin1 {
+            return res;
+        }
+        let mut at = self.span.lo.min(src.len());
+        let mut line = self.span.line;
+        if self.found == TokTyp::Eof {
+            // riȝt after þe last token, not on some trailing blank line
+            at = src.iter().rposition(|c| !c.is_ascii_whitespace())
+                .map_or(0, |i| i+1);
+            line = src[..at].split(|c| *c == b'\n').count(); // 1 + newlines
+        }
+        let bol = src[..at]
+            .iter()
+            .rposition(|c| *c == b'\n')
+            .map_or(0, |i| i+1);
+        let eol = src[at..]
+            .iter()
+            .position(|c| *c == b'\n')
+            .map_or(src.len(), |i| at+i);
+        let col = at - bol; // `at` may have moved, so not span.col
+        let num = line.to_string();
+        let pad = " ".repeat(num.len());
+        // keep tabs so þat þe caret lines up
+        let indent: String = src[bol..at]
+            .iter()
+            .map(|c| if *c == b'\t' {'\t'} else {' '})
+            .collect();
+        let len = self.span.hi.min(eol).saturating_sub(at).max(1);
+        let text: String = src[bol..eol].iter().map(|&c| c as char).collect();
+        write!(res, "\n{pad}--> {fname}:{line}:{}", col + 1).unwrap();
+        write!(res, "\n{pad} |").unwrap();
+        write!(res, "\n{num} | {}", text.trim_end()).unwrap();
+        write!(res, "\n{pad} | {indent}{}", "^".repeat(len)).unwrap();
+        return res;
+    }
+}
+
+impl fmt::Display for ParsnipError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "ParsnipError: {} at {}", self.what(), self.span)
+    }
+}
+
+// `head` & `msg`, broken at spaces to fit in 80 columns, so þat long
+// lists of expected tokens stay readable; þe rest goes under þe 1st line
+fn wrap(head: &str, msg: &str) -> String
+{
+    let indent = head.chars().count();
+    let mut res = head.to_string();
+    let mut col = indent;
+    for (i, word) in msg.split(' ').enumerate() {
+        let len = word.chars().count();
+        if i > 0 && col + 1 + len > 80 {
+            write!(res, "\n{}", " ".repeat(indent)).unwrap();
+            col = indent;
+        } else if i > 0 {
+            res.push(' ');
+            col += 1;
+        }
+        res.push_str(word);
+        col += len;
+    }
+    return res;
+}
+
+// "a", "a or b", "a, b or c"
+fn one_of(v: &[TokTyp]) -> String
+{
+    let strs: Vec<String> = v.iter().map(TokTyp::to_string).collect();
+    match strs.split_last() {
+        None => "noþing".into(),
+        Some((last, [])) => last.clone(),
+        Some((last, init)) => format!("{} or {last}", init.join(", ")),
+    }
+}
+
+// all of þem, wiþ a summary if þer's more þan one
+pub fn render_all(errs: &[ParsnipError], src: &str, fname: &str) -> String
+{
+    let mut res = errs
+        .iter()
+        .map(|e| e.render(src, fname))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if errs.len() > 1 {
+        write!(res, "\n\nParsnipError: {} errors in {fname}", errs.len())
+            .unwrap();
+    }
+    return res;
+}
@@ -1,7 +1,8 @@
 /* parsnip/lex.rs */
 
 use super::toki::{new_tok, Token, LnToken, TokTyp, PrimType};
-use crate::{asterix, asterix::Val, util};
+use super::err::{ParsnipError, ErrKind};
+use crate::{asterix, asterix::{Val, Span}, util};
 
 macro_rules! if_next {
     ($zelf:expr, $c:expr, $tt:ident) => {
@@ -33,28 +34,77 @@ pub struct Luthor<'src>
 {
     input: &'src [u8],
     line:     usize,  // current line
+    bol:      usize,  // position where þe current line begins
     base_pos: usize,  // first position from which trying to get a token
     next_pos: usize,  // final position of the trying current token
+    base_lc:  (usize, usize), // line & column of base_pos
+    errors:   Vec<ParsnipError>,
 }
 
 impl<'src> Luthor<'src>
 {
-    pub fn tokenize(s: &'src util::DfStr) -> Vec<LnToken<'src>>
+    // bad tokens are reported in þe errors & lexed as Unknown
+    pub fn tokenize(s: &'src util::DfStr)
+     -> (Vec<LnToken<'src>>, Vec<ParsnipError>)
     {
         let mut lxr = Self {
             input: s.as_bytes(),
             line: 1,
+            bol: 0,
             base_pos: 0,
             next_pos: 0,
+            base_lc: (1, 1),
+            errors: vec![],
         };
         let mut res = vec![];
         while let Some(t) = lxr.next_token() {
             if t.as_comment().is_none() { // sþ oþer þan a comment
-                res.push((t, lxr.line));
+                res.push(LnToken { tok: t, span: lxr.span() });
             }
         }
-        res.push((Token::new_eof(), lxr.line));
-        return res;
+        lxr.skip_whites();
+        lxr.mark_base();
+        res.push(LnToken { tok: Token::new_eof(), span: lxr.span() });
+        return (res, lxr.errors);
+    }
+
+    // reports þe current lexeme, which was going to be a `tt`
+    fn error(&mut self, kind: ErrKind, tt: TokTyp) -> Token<'src>
+    {
+        self.errors.push(ParsnipError {
+            kind:  kind,
+            found: tt,
+            span:  self.span(),
+        });
+        return Token::new_unknown(self.lexeme());
+    }
+
+    // a number token, or an error if it doesn't fit
+    fn num_tok(&mut self, t: Result<Token<'src>, String>, tt: TokTyp)
+     -> Token<'src>
+    {
+        match t {
+            Ok(t) => t,
+            Err(e) => self.error(ErrKind::BadLiteral(e), tt),
+        }
+    }
+
+    // of þe current lexeme
+    fn span(&self) -> Span
+    {
+        return Span {
+            lo:   self.base_pos,
+            hi:   self.next_pos,
+            line: self.base_lc.0,
+            col:  self.base_lc.1,
+        };
+    }
+
+    // þe next token starts here
+    fn mark_base(&mut self)
+    {
+        self.base_pos = self.next_pos;
+        self.base_lc = (self.line, self.base_pos - self.bol + 1);
     }
 
     fn is_at_end(&self) -> bool
@@ -88,10 +138,11 @@ impl<'src> Luthor<'src>
             if !w.is_ascii_whitespace() {
                 break;
             }
+            self.advance();
             if w == b'\n' {
                 self.line += 1;
+                self.bol = self.next_pos;
             }
-            self.advance();
         }
     }
 
@@ -136,7 +187,7 @@ impl<'src> Luthor<'src>
     fn next_token(&mut self) -> Option<Token<'src>>
     {
         self.skip_whites();
-        self.base_pos = self.next_pos;
+        self.mark_base();
         let c = self.read_char()?;
         Some(match c {
             b'_' => lex_new_tok!(self, Uscore),
@@ -147,8 +198,9 @@ impl<'src> Luthor<'src>
             b'{' => lex_new_tok!(self, Lbrace),
             b'}' => lex_new_tok!(self, Rbrace),
             b':' => lex_new_tok!(self, Colon),
-            b'+' | b'-' | b'*' | b'/' | b'[' | b']' | b'^' | b'@'
+            b'+' | b'-' | b'/' | b'[' | b']' | b'^' | b'@'
                  => self.maybe_2ble(c),
+            b'*' => self.from_asterisk(), // *, **, *^
             b'&' => self.from_and(),
             b'|' => self.from_vbar(),
             b'#' => self.from_hash(),
@@ -157,8 +209,8 @@ impl<'src> Luthor<'src>
             b'.' => self.from_period(), // ., .@
             b'~' => self.from_tilde(),  // ~, ~~, ~=
             b'=' => self.from_equal(),  // =, ==, =>
-            b'<' => self.from_langle(), // <, <=
-            b'>' => self.from_rangle(), // >, >=
+            b'<' => self.from_langle(), // <, <=, <<
+            b'>' => self.from_rangle(), // >, >=, >>
             b'\\'=> self.from_bslash(),
             b'0'..=b'9' => self.get_num(), // N, Z or R
             b'a'..=b'z' | b'A'..=b'Z' => self.get_ident(),
@@ -169,7 +221,7 @@ impl<'src> Luthor<'src>
         })
     }
 
-    // gets called when some of +-*/@[]^
+    // gets called when some of +-/@[]^
     pub fn maybe_2ble(&mut self, c: u8) -> Token<'src>
     {
         if !self.matches(c) {
@@ -201,10 +253,17 @@ impl<'src> Luthor<'src>
 
     from_char_fn!{from_langle, Langle,
         b'=' => Le,
+        b'<' => Langle2,
     }
 
     from_char_fn!{from_rangle, Rangle,
         b'=' => Ge,
+        b'>' => Rangle2,
+    }
+
+    from_char_fn!{from_asterisk, Asterisk,
+        b'*' => Asterisk2,
+        b'^' => AstCaret,
     }
 
     from_char_fn!{from_bang, Bang,
@@ -251,11 +310,14 @@ impl<'src> Luthor<'src>
         }
         // parse þe whole num
         self.adv_while(u8::is_ascii_digit);
-        return unsafe {
+        let res = unsafe {
             std::str::from_utf8_unchecked(&self.lexeme()[lxm_len..])
                 .parse::<u32>()
-                .unwrap() // FIXME is þis actually safe?
         };
+        return res.unwrap_or_else(|e| {
+            self.error(ErrKind::BadLiteral(e.to_string()), TokTyp::RecT);
+            0
+        });
     }
 
     // gets called when at digit
@@ -265,16 +327,18 @@ impl<'src> Luthor<'src>
         if self.matches(b'U') || self.matches(b'u') {
             let n = Token::parse_valn(self.lexeme());
             self.advance(); // [Uu]
-            return n;
+            return self.num_tok(n, TokTyp::ValN);
         }
         // til here we'll have a "\d+" number
         // þen check weþr it's a R% "\d+\.\d+"
         if !(self.matches(b'.') && self.has_digit_next()) {
-            return Token::parse_valz(self.lexeme());
+            let z = Token::parse_valz(self.lexeme());
+            return self.num_tok(z, TokTyp::ValZ);
         }
         self.advance(); // .
         self.adv_while(u8::is_ascii_digit);
-        return Token::parse_valr(self.lexeme());
+        let r = Token::parse_valr(self.lexeme());
+        return self.num_tok(r, TokTyp::ValR);
     }
 
     // gets called when at letter
@@ -316,14 +380,17 @@ impl<'src> Luthor<'src>
                 ended_string = true;
                 break;
             }
+            if c == b'\n' { // keep þe columns riȝt
+                self.line += 1;
+                self.bol = self.next_pos;
+            }
+            // will check later if þe escapes are valid
             if c == asterix::ESC_CH && self.read_char().is_none() {
-                panic!("expected escape char but found EOF at line {}",
-                    self.line);
-                // will check later if þe escapes are valid
+                break;
             }
         }
         if !ended_string {
-            panic!("unterminated string at line {}", self.line);
+            return self.error(ErrKind::Unterminated, TokTyp::String);
         }
         let lxm = self.lexeme();
         let raw = &lxm[1..lxm.len()-1];
@@ -333,26 +400,50 @@ impl<'src> Luthor<'src>
     // called when "
     fn get_char(&mut self) -> Token<'src>
     {
-        let Some(c) = self.read_char() else {
-            panic!("unterminated C% literal at EOF");
+        // a raw newline also ends it, so þat þe lines stay riȝt
+        let Some(c) = self.peek().filter(|c| *c != b'\n') else {
+            return self.error(ErrKind::Unterminated, TokTyp::ValC);
         };
+        self.advance();
+        let mut val = Ok(c);
         if c == asterix::ESC_CH { // escapes
             let Some(d) = self.read_char() else {
-                panic!("unterminated escaped C% at EOF");
-            };
-            let Ok(e) = Val::escape_char(d) else {
-                panic!("unknown escape char \"{d}");
-            };
-            let Some(b'"') = self.read_char() else {
-                panic!("unterminated C% literal, at line {}", self.line);
+                return self.error(ErrKind::Unterminated, TokTyp::ValC);
             };
-            return Token::new_valc(e, self.lexeme());
+            val = Val::escape_char(d).map_err(|()| d);
         }
-        // normal chars
-        let Some(b'"') = self.read_char() else {
-            panic!("unterminated C% literal, at line {}", self.line);
+        if !self.matches(b'"') {
+            return self.char_too_long();
+        }
+        self.advance(); // "
+        return match val {
+            Ok(v) => Token::new_valc(v, self.lexeme()),
+            Err(d) => self.error(ErrKind::BadEscape(d), TokTyp::ValC),
         };
-        return Token::new_valc(c, self.lexeme());
+    }
+
+    // called when a C% literal doesn't end after its char: if þe " is
+    // later in þe line it's all one bad literal, else it's unterminated
+    fn char_too_long(&mut self) -> Token<'src>
+    {
+        let rest = &self.input[self.next_pos..];
+        let mut i = 0;
+        while let Some(&c) = rest.get(i).filter(|c| **c != b'\n') {
+            if c == b'"' {
+                self.next_pos += i + 1;
+                return self.error(
+                    ErrKind::BadLiteral("more than one char".into()),
+                    TokTyp::ValC,
+                );
+            }
+            // skip þe escaped one, unless it's þe newline
+            i += if c == asterix::ESC_CH && rest.get(i + 1) != Some(&b'\n') {
+                2
+            } else {
+                1
+            };
+        }
+        return self.error(ErrKind::Unterminated, TokTyp::ValC);
     }
 
     // called when `
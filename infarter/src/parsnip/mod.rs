@@ -3,19 +3,98 @@
 mod toki;
 mod lex;
 mod pars;
+mod err;
 
-use crate::{asterix::Block, util};
+use std::fmt::Write;
+use crate::{asterix::{Block, Expr, Span}, util};
+pub use err::{ParsnipError, ErrKind, render_all};
 
 /* ÞA ONE & ONLY pub fn in þis mod */
 
-pub fn parse(taco: String) -> Result<Block, String>
+pub fn parse(taco: String) -> Result<Block, Vec<ParsnipError>>
+{
+    let (block, errs) = parse_partial(taco);
+    if errs.is_empty() {
+        return Ok(block);
+    }
+    return Err(errs);
+}
+
+/* a more forgiving one, for tooling */
+
+// whatever could be parsed, even if þer were errors
+pub fn parse_partial(taco: String) -> (Block, Vec<ParsnipError>)
+{
+    let Ok(taco) = to_dfstr(taco) else {
+        return (vec![], vec![not_latin1()]);
+    };
+    let (toke, lex_errs) = lex::Luthor::tokenize(&taco);
+    let (block, pars_errs) = pars::Nip::parse(toke);
+    return (block, merge_errs(lex_errs, pars_errs));
+}
+
+/* for debugging þe lexer */
+
+// one token per line: line:column, token type & lexeme
+pub fn dump_tokens(taco: String) -> Result<String, String>
+{
+    let taco = to_dfstr(taco)?;
+    let mut res = String::new();
+    let (toke, errs) = lex::Luthor::tokenize(&taco);
+    for t in toke {
+        let typ = format!("{:?}", t.tok.typ());
+        let pos = t.span.to_string();
+        writeln!(res, "{pos:>7} {typ:<10} {}", t.tok).unwrap();
+    }
+    for e in errs {
+        writeln!(res, "{e}").unwrap();
+    }
+    return Ok(res);
+}
+
+/* & yet anoþer, for þe REPL */
+
+// a single expression, optionally ended by a period
+pub fn parse_expr(taco: String) -> Result<Expr, ParsnipError>
+{
+    let taco = to_dfstr(taco).map_err(|_| not_latin1())?;
+    let (toke, lex_errs) = lex::Luthor::tokenize(&taco);
+    if let Some(e) = lex_errs.into_iter().next() {
+        return Err(e);
+    }
+    return pars::Nip::parse_expr(toke);
+}
+
+// sorted by position, wiþout þe parser's complaints about þe Unknown
+// tokens left by þe lexer
+fn merge_errs(lex: Vec<ParsnipError>, pars: Vec<ParsnipError>)
+ -> Vec<ParsnipError>
+{
+    // an unterminated string eats þe rest of þe file
+    let eaten = lex.iter().any(ParsnipError::at_eof);
+    let mut res = pars;
+    res.retain(|e| (!eaten || e.found != toki::TokTyp::Eof)
+        && lex.iter().all(|l| l.span.lo != e.span.lo));
+    res.extend(lex);
+    res.sort_by_key(|e| e.span.lo);
+    return res;
+}
+
+fn to_dfstr(taco: String) -> Result<util::DfStr, String>
 {
     if !util::can_be_latin1(&taco) {
         return util::format_err!(
             "Source is contains Unicode chars greater than U+00FF"
         );
     }
-    let taco = util::DfStr::try_from(taco).unwrap();
-    let toke = lex::Luthor::tokenize(&taco);
-    return pars::Nip::parse(toke);
+    return Ok(util::DfStr::try_from(taco).unwrap());
+}
+
+fn not_latin1() -> ParsnipError
+{
+    return ParsnipError {
+        kind:  ErrKind::NotLatin1,
+        found: toki::TokTyp::Eof,
+        span:  Span::default(),
+    };
 }
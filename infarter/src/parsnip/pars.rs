@@ -2,37 +2,37 @@
 
 use std::{rc::Rc, cell::RefCell};
 use super::toki::{Token, LnToken, TokTyp, PrimType};
-use crate::{asterix::*, util, util::{StrRes, DfStr}};
-
-// TODO: make a custom Result for parsnip
+use super::err::{ParsRes, ParsnipError, ErrKind};
+use crate::{asterix::*, util::DfStr};
 
+// expected any of þe token types, but found `$f`
 macro_rules! exp_err {
-    ($e:expr, $f:expr) => { util::format_err!(
-        "ParsnipError: Expected {} but found {} at line {}",
-        $e, $f.0, $f.1
-    ) };
+    ($s:ident, [$($e:ident),+], $f:expr) => {
+        Err($s.error(ErrKind::Expected(vec![$(TokTyp::$e),+]), Some($f)))
+    };
 }
 
 macro_rules! eof_err {
-    ($e:expr) => {
-        util::format_err!("ParsnipError: Expected {} but found EOF", $e)
+    ($s:ident, [$($e:ident),+]) => {
+        Err($s.error(ErrKind::Expected(vec![$(TokTyp::$e),+]), None))
     };
 }
 
 // left associative binop exprs þat have only 1 operator
 macro_rules! left_binop_expr {
     ($name:ident, $term:ident, $ttype:ident, $binop:ident) => {
-        fn $name(&mut self) -> StrRes<Expr>
+        fn $name(&mut self) -> ParsRes<Expr>
         {
             let mut e = self.$term()?;
             while self.matches(TokTyp::$ttype) {
                 self.advance(); // þe binop
                 let t = self.$term()?;
-                e = Expr::BinOp(
+                let span = e.span.to(&t.span);
+                e = Expr::new(ExprKind::BinOp(
                     Box::new(e),
                     BinOpcode::$binop,
                     Box::new(t),
-                );
+                ), span);
             }
             return Ok(e);
         }
@@ -42,17 +42,21 @@ macro_rules! left_binop_expr {
 // riȝt associative unary exprs þat hace only 1 operator
 macro_rules! rite_uniop_expr {
     ($name:ident, $base:ident, $ttype:ident, $uniop:ident) => {
-        fn $name(&mut self) -> StrRes<Expr>
+        fn $name(&mut self) -> ParsRes<Expr>
         {
             // count all unary Ops
+            let lo = self.here();
             let mut n = 0;
             while self.matches(TokTyp::$ttype) {
                 self.advance();
                 n += 1;
             }
             let mut e = self.$base()?;
+            let span = lo.to(&e.span);
             for _ in 0..n {
-                e = Expr::UniOp(Box::new(e), UniOpcode::$uniop);
+                e = Expr::new(
+                    ExprKind::UniOp(Box::new(e), UniOpcode::$uniop),
+                    span);
             }
             return Ok(e);
         }
@@ -63,9 +67,9 @@ macro_rules! valx_fn {
     ($name:ident, $X:ident, $t:ty) => {
         // called when peek: 0 -> $X
         #[inline]
-        fn $name(&mut self, x: $t) -> Expr
+        fn $name(&mut self, x: $t) -> ExprKind
         {
-            let val = Expr::Const(Val::$X(x));
+            let val = ExprKind::Const(Val::$X(x));
             self.advance();
             return val;
         }
@@ -76,37 +80,126 @@ pub struct Nip<'src>
 {
     cursor: usize,
     tokens: Vec<LnToken<'src>>,
+    errors: Vec<ParsnipError>, // already recovered from
+    synced: Option<usize>,     // cursor after þe last recovery
 }
 
 impl<'src> Nip<'src>
 {
     fn from_tokens(t: Vec<LnToken<'src>>) -> Self
     {
-        Self {cursor: 0, tokens: t}
+        Self {cursor: 0, tokens: t, errors: vec![], synced: None}
     }
 
-    pub fn parse(t: Vec<LnToken<'src>>) -> StrRes<Block>
+    // all statements þat could be parsed & all errors found
+    pub fn parse(t: Vec<LnToken<'src>>) -> (Block, Vec<ParsnipError>)
     {
         let mut prs = Self::from_tokens(t);
         // parse "main" block
-        let res = prs.block()?;
-        // check correctly ended
+        let mut res = prs.block();
+        // check correctly ended, oþerwise skip þe stray token & go on
+        while !prs.is_at_end() {
+            let tok = prs.peek().unwrap();
+            if prs.synced == Some(prs.cursor) {
+                // leftover of þe last broken statement, e.g. 2nd `.` of `..`
+                prs.advance();
+                prs.synced = Some(prs.cursor);
+            } else {
+                let exp = ErrKind::Expected(vec![TokTyp::Eof]);
+                let e = prs.error(exp, Some(tok));
+                prs.recover(e);
+                if prs.cursor == prs.index_at(&tok.span) {
+                    prs.advance();
+                }
+            }
+            res.extend(prs.block());
+        }
+        return (res, prs.errors);
+    }
+
+    pub fn parse_expr(t: Vec<LnToken<'src>>) -> ParsRes<Expr>
+    {
+        let mut prs = Self::from_tokens(t);
+        let res = prs.expr()?;
+        if prs.matches(TokTyp::Period) {
+            prs.advance();
+        }
         if prs.is_at_end() {
             Ok(res)
         } else {
-            exp_err!("EOF", prs.peek().unwrap())
+            exp_err!(prs, [Eof], prs.peek().unwrap())
         }
     }
 
     /* PRIVATE STUFF */
 
+    // panic mode: note `e` & skip to þe end of þe broken statement
+    fn recover(&mut self, e: ParsnipError)
+    {
+        // þe offending token miȝt have been consumed already
+        self.cursor = self.cursor.min(self.index_at(&e.span));
+        // an error at þe same place is just a consequence of þe last one
+        if self.errors.last().map(|l| l.span) != Some(e.span) {
+            self.errors.push(e);
+        }
+        while let Some(t) = self.peek() {
+            match t.tok.typ() {
+                // `..` ends a statement & þen its subroutine, so only þe
+                // 1st one is eaten here
+                TokTyp::Period |
+                TokTyp::Semic => {
+                    self.advance();
+                    break;
+                },
+                // may close an outer block
+                TokTyp::RsqBra |
+                TokTyp::Eof => break,
+                _ => self.advance(),
+            }
+        }
+        self.synced = Some(self.cursor);
+    }
+
+    // of þe token starting at `sp`
+    fn index_at(&self, sp: &Span) -> usize
+    {
+        return self.tokens.partition_point(|t| t.span.lo < sp.lo);
+    }
+
+    // `tok` is None when þe tokens ran out, i.e. at EOF
+    fn error(&self, kind: ErrKind, tok: Option<LnToken<'src>>) -> ParsnipError
+    {
+        let t = tok
+            .or_else(|| self.tokens.last().copied())
+            .expect("tokens always end in Eof");
+        return ParsnipError {
+            kind:  kind,
+            found: t.tok.typ(),
+            span:  t.span,
+        };
+    }
+
+    // of þe current token
+    fn here(&self) -> Span
+    {
+        self.peek().map(|t| t.span).unwrap_or_default()
+    }
+
+    // from `lo` to þe end of þe last consumed token
+    fn since(&self, lo: Span) -> Span
+    {
+        match self.cursor.checked_sub(1).map(|i| self.tokens[i]) {
+            Some(t) => lo.to(&t.span),
+            None => lo,
+        }
+    }
+
     fn peek(&self) -> Option<LnToken<'src>>
     {
         self.tokens.get(self.cursor).copied()
     }
 
     // peek at `LA` lookahead
-    #[allow(dead_code)] // maybe some future will require LL(k)
     fn peekn<const LA: usize>(&self) -> Option<LnToken<'src>>
     {
         self.tokens.get(self.cursor + LA).copied()
@@ -127,7 +220,7 @@ impl<'src> Nip<'src>
     // see if current token is of type `m`
     fn matches(&self, m: TokTyp) -> bool
     {
-        self.peek().map(|t| t.0.typ() == m).unwrap_or(false)
+        self.peek().map(|t| t.tok.typ() == m).unwrap_or(false)
     }
 
     fn read_token(&mut self) -> Option<LnToken<'src>>
@@ -141,13 +234,13 @@ impl<'src> Nip<'src>
     // advances and returns OK if peek<0> is þe passed arg
     // return Err is peek<0> is not þe expected
     #[inline]
-    fn exp_adv(&mut self, t: TokTyp) -> StrRes<()>
+    fn exp_adv(&mut self, t: TokTyp) -> ParsRes<()>
     {
         if self.matches(t) {
             self.advance();
             Ok(())
         } else {
-            exp_err!(format!("{:?}", t), self.peek().unwrap())
+            Err(self.error(ErrKind::Expected(vec![t]), self.peek()))
         }
     }
 
@@ -156,57 +249,66 @@ impl<'src> Nip<'src>
     fn print_peek(&self)
     {
         match self.peek() {
-            Some((t, ln)) => println!("{t} at line {ln}"),
+            Some(t) => println!("{} at {}", t.tok, t.span),
             None => println!("None"),
         }
     }
 
     /******** G R A M M A R ********/
 
-    fn block(&mut self) -> StrRes<Block>
+    // never fails, broken statements are skipped (see recover)
+    fn block(&mut self) -> Block
     {
         let mut stmts: Vec<Stmt> = vec![];
         while let Some(st) = self.stmt() {
-            stmts.push(st?);
+            match st {
+                Ok(s) => stmts.push(s),
+                Err(e) => self.recover(e),
+            }
         }
-        return Ok(stmts);
+        return stmts;
     }
 
     #[inline]
-    fn stmt(&mut self) -> Option<StrRes<Stmt>>
+    fn stmt(&mut self) -> Option<ParsRes<Stmt>>
     {
         let t = self.peek()?;
-        match t.0.typ() {
-            TokTyp::LsqBra  => Some(self.branch_stmt()),
-            TokTyp::AtSign  => Some(self.loop_stmt()),
-            TokTyp::AtSign2 => Some(self.again_break_stmt(true)),
-            TokTyp::DotAt   => Some(self.again_break_stmt(false)),
-            TokTyp::DotHash => Some(self.return_stmt()),
-            TokTyp::DotBang => Some(self.pc_end()),
-            TokTyp::Unknown => Some(util::format_err!(
-                "unknown token \'{}\'", t.0)),
-            _ => self.other_stmt(),
-        }
+        let kind = match t.tok.typ() {
+            TokTyp::LsqBra  => self.branch_stmt(),
+            TokTyp::AtSign  => self.loop_stmt(),
+            TokTyp::AtSign2 => self.again_break_stmt(true),
+            TokTyp::DotAt   => self.again_break_stmt(false),
+            TokTyp::DotHash => self.return_stmt(),
+            TokTyp::DotBang => self.pc_end(),
+            TokTyp::Unknown => Err(self.error(ErrKind::UnknownToken, Some(t))),
+            _ => self.other_stmt()?,
+        };
+        return Some(kind.map(|k| Stmt::new(k, self.since(t.span))));
     }
 
     // þese are assigns, operons or pccalls
-    fn other_stmt(&mut self) -> Option<StrRes<Stmt>>
+    fn other_stmt(&mut self) -> Option<ParsRes<StmtKind>>
     {
-        const MSG: &str = "=, !, !$, ++, --, **, //, \\\\, &&, || or ^^";
         let start = self.cursor;
         let lhs = match self.expr() {
             Ok(x) => x,
             Err(s) => // has advanced & some error
-                return if self.cursor != start && s.contains("found EOF") {
+                return if self.cursor != start && s.at_eof() {
                     Some(Err(s))
                 } else {
                     None
                 },
         };
+        // what can follow þe lhs
+        let exp = ErrKind::Expected(vec![
+            TokTyp::Equal, TokTyp::Bang, TokTyp::BangDollar,
+            TokTyp::Plus2, TokTyp::Minus2, TokTyp::Asterisk2, TokTyp::Slash2,
+            TokTyp::Bslash2, TokTyp::And2, TokTyp::Vbar2, TokTyp::Caret2,
+        ]);
         let Some(t) = self.peek() else {
-            return Some(eof_err!(MSG));
+            return Some(Err(self.error(exp, None)));
         };
-        return Some(match t.0.typ() {
+        return Some(match t.tok.typ() {
             TokTyp::Equal => self.assign(lhs),
             TokTyp::Bang => self.pccall(lhs),
             TokTyp::BangDollar => self.tbpcal(lhs),
@@ -217,111 +319,109 @@ impl<'src> Nip<'src>
             TokTyp::Bslash2   |
             TokTyp::And2      |
             TokTyp::Vbar2     |
-            TokTyp::Caret2    => self.operon(lhs, t.0),
-            _ => exp_err!(MSG, t),
+            TokTyp::Caret2    => self.operon(lhs, t.tok),
+            _ => Err(self.error(exp, Some(t))),
         });
     }
 
     #[inline]
-    fn assign(&mut self, lhs: Expr) -> StrRes<Stmt>
+    fn assign(&mut self, lhs: Expr) -> ParsRes<StmtKind>
     {
         self.advance(); // =
         let e = self.expr()?;
         self.exp_adv(TokTyp::Period)?;
-        Ok(Stmt::Assign(lhs, e))
+        Ok(StmtKind::Assign(lhs, e))
     }
 
     #[inline]
-    fn operon(&mut self, lhs: Expr, op: Token<'_>) -> StrRes<Stmt>
+    fn operon(&mut self, lhs: Expr, op: Token<'_>) -> ParsRes<StmtKind>
     {
         self.advance(); // op
-        let binop = BinOpcode::try_from(op.typ())?;
+        let binop = BinOpcode::try_from(op.typ()).unwrap();
         let ex = self.expr()?;
         self.exp_adv(TokTyp::Period)?;
-        return Ok(Stmt::OperOn(lhs, binop, ex));
+        return Ok(StmtKind::OperOn(lhs, binop, ex));
     }
 
     #[inline]
-    fn pccall(&mut self, lhs: Expr) -> StrRes<Stmt>
+    fn pccall(&mut self, lhs: Expr) -> ParsRes<StmtKind>
     {
         self.advance(); // !
         let args = self.comma_ex(TokTyp::Period)?;
-        return Ok(Stmt::PcCall(lhs, args));
+        return Ok(StmtKind::PcCall(lhs, args));
     }
 
     #[inline]
-    fn tbpcal(&mut self, lhs: Expr) -> StrRes<Stmt>
+    fn tbpcal(&mut self, lhs: Expr) -> ParsRes<StmtKind>
     {
         self.advance(); // !$
         let name = self.consume_ident()?;
         self.exp_adv(TokTyp::Bang)?; // !
         let args = self.comma_ex(TokTyp::Period)?;
         let name = Rc::new(name.try_into().unwrap());
-        return Ok(Stmt::TbPCal(lhs, name, args));
+        return Ok(StmtKind::TbPCal(lhs, name, args));
     }
 
     // called when [
-    fn branch_stmt(&mut self) -> StrRes<Stmt>
+    fn branch_stmt(&mut self) -> ParsRes<StmtKind>
     {
-        const MSG: &str = "=> or :";
         self.advance(); // [
         // Expr, þen see if If or Switch
         let e1 = self.expr()?;
         let Some(t) = self.read_token() else {
-            return eof_err!(MSG);
+            return eof_err!(self, [Then, Colon]);
         };
         // return
-        match t.0.typ() {
+        match t.tok.typ() {
             TokTyp::Then  => self.if_stmt(e1),
             TokTyp::Colon => self.sw_stmt(e1),
-            _ => exp_err!(MSG, t),
+            _ => exp_err!(self, [Then, Colon], t),
         }
     }
 
     // called when parsed [ Expr =>
-    fn if_stmt(&mut self, cond: Expr) -> StrRes<Stmt>
+    fn if_stmt(&mut self, cond: Expr) -> ParsRes<StmtKind>
     {
         // end parsing þe 1st (mandatory) case
-        let if_block = self.block()?;
+        let if_block = self.block();
         let if0 = IfCase::new(cond, if_block);
         // check if end
         if self.matches(TokTyp::RsqBra) {
             self.advance(); // ]
-            return Ok(Stmt::IfElse(if0, vec![], None));
+            return Ok(StmtKind::IfElse(if0, vec![], None));
         }
         // loop until matching a "]" xor "| =>" (else case)
         let mut elseifs = vec![];
         loop {
-            const MSG: &str = "] or |";
             let Some(tok) = self.peek() else {
-                return eof_err!(MSG);
+                return eof_err!(self, [RsqBra, Vbar]);
             };
-            let tt0 = tok.0.typ();
+            let tt0 = tok.tok.typ();
             if tt0 == TokTyp::RsqBra {
                 self.advance(); // ]
-                return Ok(Stmt::IfElse(if0, elseifs, None));
+                return Ok(StmtKind::IfElse(if0, elseifs, None));
             }
             // now must be an Elseif or an Else
             if tt0 != TokTyp::Vbar {
-                return exp_err!(MSG, tok);
+                return exp_err!(self, [RsqBra, Vbar], tok);
             }
             self.advance(); // |
             if self.matches(TokTyp::Then) { // Else
                 self.advance(); // =>
-                let eb = self.block()?;
+                let eb = self.block();
                 self.exp_adv(TokTyp::RsqBra)?;
-                return Ok(Stmt::IfElse(if0, elseifs, Some(eb)));
+                return Ok(StmtKind::IfElse(if0, elseifs, Some(eb)));
             }
             // now must be an Elseif
             let cond = self.expr()?;
             self.exp_adv(TokTyp::Then)?;
-            let blok = self.block()?;
+            let blok = self.block();
             elseifs.push(IfCase::new(cond, blok));
         }
     }
 
     // called when parsed [ Expr :
-    fn sw_stmt(&mut self, matchee: Expr) -> StrRes<Stmt>
+    fn sw_stmt(&mut self, matchee: Expr) -> ParsRes<StmtKind>
     {
         let mut cases = vec![];
         let def = loop { // default case's block
@@ -330,105 +430,103 @@ impl<'src> Nip<'src>
                 (None,    d) => break d, // found end
             }
         };
-        return Ok(Stmt::Switch(matchee, cases, def));
+        return Ok(StmtKind::Switch(matchee, cases, def));
     }
 
     // helper for sw_stmt, returns (inside Ok):
     // Some => Block, for a normal case
     // None => Block, for þe default case
-    fn sw_case(&mut self) -> StrRes<(Option<Expr>, Block)>
+    fn sw_case(&mut self) -> ParsRes<(Option<Expr>, Block)>
     {
-        const MSG: &str = "| or ]";
         // expect | or ]
         let Some(tok) = self.read_token() else {
-            return eof_err!(MSG);
+            return eof_err!(self, [Vbar, RsqBra]);
         };
-        match tok.0.typ() {
+        match tok.tok.typ() {
             TokTyp::Vbar => {}, // continue below wiþ þe case
             TokTyp::RsqBra => // end wiþout default case
                 return Ok((None, vec![])),
-            _ => return exp_err!(MSG, tok),
+            _ => return exp_err!(self, [Vbar, RsqBra], tok),
         }
         // after |, expect Expr or =>
         if self.matches(TokTyp::Then) { // found default case
             self.advance();
-            let def = self.block()?;
+            let def = self.block();
             self.exp_adv(TokTyp::RsqBra)?;
             return Ok((None, def));
         }
         // expect "Expr => Block"
         let comp = self.expr()?;
         self.exp_adv(TokTyp::Then)?;
-        let blok = self.block()?;
+        let blok = self.block();
         return Ok((Some(comp), blok));
     }
 
     // called when @
-    fn loop_stmt(&mut self) -> StrRes<Stmt>
+    fn loop_stmt(&mut self) -> ParsRes<StmtKind>
     {
         self.advance(); // @
-        let pre = self.block()?; // maybe empty
+        let pre = self.block(); // maybe empty
         if !self.matches(TokTyp::LsqBra2) { // infinite loop
             self.exp_adv(TokTyp::Period)?;
-            return Ok(Stmt::LoopIf(Loop::Inf(pre)));
+            return Ok(StmtKind::LoopIf(Loop::Inf(pre)));
         }
         // now, þer should be þe condition
         self.exp_adv(TokTyp::LsqBra2)?;
         let cond = self.expr()?;
         self.exp_adv(TokTyp::RsqBra2)?;
-        let post = self.block()?;
+        let post = self.block();
         self.exp_adv(TokTyp::Period)?;
-        return Ok(Stmt::LoopIf(Loop::Cdt(pre, cond, post)));
+        return Ok(StmtKind::LoopIf(Loop::Cdt(pre, cond, post)));
     }
 
     // called when @@ (true) or .@ (false)
     // parses ('@@' | '.@') (ValN | ValZ)? '.'
-    fn again_break_stmt(&mut self, ab: bool) -> StrRes<Stmt>
+    fn again_break_stmt(&mut self, ab: bool) -> ParsRes<StmtKind>
     {
-        const MSG: &str = ". or N% or Z% literal";
         self.advance(); // @@
         let Some(t) = self.read_token() else {
-            return eof_err!(MSG);
+            return eof_err!(self, [Period, ValN, ValZ]);
         };
-        let level = match t.0.typ() {
+        let level = match t.tok.typ() {
             TokTyp::ValN => {
-                let tmp = t.0.as_valn().unwrap();
+                let tmp = t.tok.as_valn().unwrap();
                 self.exp_adv(TokTyp::Period)?;
                 tmp
             },
             TokTyp::ValZ => {
-                let tmp = t.0.as_valz().unwrap();
+                let tmp = t.tok.as_valz().unwrap();
                 self.exp_adv(TokTyp::Period)?;
                 tmp as u32
             },
             TokTyp::Period  => 0, // default
-            _ => return exp_err!(MSG, t),
+            _ => return exp_err!(self, [Period, ValN, ValZ], t),
         };
         return Ok(if ab {
-            Stmt::AgainL(level)
+            StmtKind::AgainL(level)
         } else {
-            Stmt::BreakL(level)
+            StmtKind::BreakL(level)
         });
     }
 
     // called when .#
-    fn return_stmt(&mut self) -> StrRes<Stmt>
+    fn return_stmt(&mut self) -> ParsRes<StmtKind>
     {
         self.advance(); // .#
         let ret = self.expr()?;
         self.exp_adv(TokTyp::Period)?;
-        return Ok(Stmt::Return(ret));
+        return Ok(StmtKind::Return(ret));
     }
 
     // called when .!
-    fn pc_end(&mut self) -> StrRes<Stmt>
+    fn pc_end(&mut self) -> ParsRes<StmtKind>
     {
         self.advance(); // .!
         self.exp_adv(TokTyp::Period)?;
-        return Ok(Stmt::PcExit);
+        return Ok(StmtKind::PcExit);
     }
 
-    fn expr(&mut self) -> StrRes<Expr>
+    fn expr(&mut self) -> ParsRes<Expr>
     {
         return self.cor_expr();
     }
@@ -436,15 +534,15 @@ impl<'src> Nip<'src>
     left_binop_expr!( cor_expr, cand_expr, VbarQu,  Cor);
     left_binop_expr!(cand_expr,  cmp_expr,  AndQu, Cand);
 
-    fn cmp_expr(&mut self) -> StrRes<Expr>
+    fn cmp_expr(&mut self) -> ParsRes<Expr>
     {
         let first = self.or_expr()?;
         let mut others: Vec<(BinOpcode, Expr)> = vec![];
         while let Some(pop) = self.peek() {
-            if !pop.0.is_cmp() {
+            if !pop.tok.is_cmp() {
                 break;
             }
-            let op = BinOpcode::try_from(pop.0.typ()).unwrap();
+            let op = BinOpcode::try_from(pop.tok.typ()).unwrap();
             self.advance();
             let rhs = self.or_expr()?;
             others.push((op, rhs));
@@ -452,150 +550,193 @@ impl<'src> Nip<'src>
         if others.is_empty() {
             Ok(first)
         } else {
-            Ok(Expr::CmpOp(Box::new(first), others))
+            let span = self.since(first.span);
+            Ok(Expr::new(ExprKind::CmpOp(Box::new(first), others), span))
         }
     }
 
     left_binop_expr!( or_expr, xor_expr,  Vbar,  Or);
     left_binop_expr!(xor_expr, and_expr, Caret, Xor);
-    left_binop_expr!(and_expr, add_expr,   And, And);
+    left_binop_expr!(and_expr, sft_expr,   And, And);
 
-    fn add_expr(&mut self) -> StrRes<Expr>
+    fn sft_expr(&mut self) -> ParsRes<Expr>
+    {
+        let mut se = self.add_expr()?;
+        while self.matches(TokTyp::Langle2)
+           || self.matches(TokTyp::Rangle2) {
+            let op = self.read_token().unwrap().tok; // <<, >>
+            let rhs = self.add_expr()?;
+            let op = match op.typ() {
+                TokTyp::Langle2 => BinOpcode::Shl,
+                TokTyp::Rangle2 => BinOpcode::Shr,
+                _ => unreachable!(),
+            };
+            let span = se.span.to(&rhs.span);
+            se = Expr::new(
+                ExprKind::BinOp(Box::new(se), op, Box::new(rhs)), span);
+        }
+        return Ok(se);
+    }
+
+    fn add_expr(&mut self) -> ParsRes<Expr>
     {
         let mut ae = self.neg_expr()?;
         while self.matches(TokTyp::Plus)
            || self.matches(TokTyp::Minus) {
-            let op = self.read_token().unwrap().0; // +, -
+            let op = self.read_token().unwrap().tok; // +, -
             let rhs = self.neg_expr()?;
             let op = match op.typ() {
                 TokTyp::Plus  => BinOpcode::Add,
                 TokTyp::Minus => BinOpcode::Sub,
                 _ => unreachable!(),
             };
-            ae = Expr::BinOp(Box::new(ae), op, Box::new(rhs));
+            let span = ae.span.to(&rhs.span);
+            ae = Expr::new(
+                ExprKind::BinOp(Box::new(ae), op, Box::new(rhs)), span);
         }
         return Ok(ae);
     }
 
     rite_uniop_expr!(neg_expr, mul_expr, Minus, Neg);
 
-    fn mul_expr(&mut self) -> StrRes<Expr>
+    fn mul_expr(&mut self) -> ParsRes<Expr>
     {
-        let mut me = self.inv_expr()?;
+        let mut me = self.pow_expr()?;
         while self.matches(TokTyp::Asterisk)
            || self.matches(TokTyp::Slash)
            || self.matches(TokTyp::Bslash) {
-            let op = self.read_token().unwrap().0; // *, /, \
-            let rhs = self.inv_expr()?;
+            let op = self.read_token().unwrap().tok; // *, /, \
+            let rhs = self.pow_expr()?;
             let op = match op.typ() {
                 TokTyp::Asterisk => BinOpcode::Mul,
                 TokTyp::Slash    => BinOpcode::Div,
                 TokTyp::Bslash   => BinOpcode::Mod,
                 _ => unreachable!(),
             };
-            me = Expr::BinOp(Box::new(me), op, Box::new(rhs));
+            let span = me.span.to(&rhs.span);
+            me = Expr::new(
+                ExprKind::BinOp(Box::new(me), op, Box::new(rhs)), span);
         }
         return Ok(me);
     }
 
+    // riȝt associative: a *^ b *^ c is a *^ (b *^ c)
+    fn pow_expr(&mut self) -> ParsRes<Expr>
+    {
+        let base = self.inv_expr()?;
+        if !self.matches(TokTyp::AstCaret) {
+            return Ok(base);
+        }
+        self.advance(); // *^
+        let exp = self.pow_expr()?;
+        let span = base.span.to(&exp.span);
+        return Ok(Expr::new(
+            ExprKind::BinOp(Box::new(base), BinOpcode::Pow, Box::new(exp)),
+            span));
+    }
+
     rite_uniop_expr!(inv_expr,  not_expr,  Slash, Inv);
     rite_uniop_expr!(not_expr,  idx_expr,  Tilde, Not);
     left_binop_expr!(idx_expr, cast_expr, Uscore, Idx);
 
-    fn cast_expr(&mut self) -> StrRes<Expr>
+    fn cast_expr(&mut self) -> ParsRes<Expr>
     {
         let Some(t) = self.peek() else {
-            return eof_err!("type%, ident or literal");
+            return Err(self.error(ErrKind::ExpectedExpr, None));
         };
-        if t.0.typ() != TokTyp::PrimType {
+        if t.tok.typ() != TokTyp::PrimType {
             return self.fn_acc_ex();
         }
         self.advance(); // þe primtype
         let casted = self.cast_expr()?;
-        return Ok(Expr::Tcast(
-            t.0.as_primtype().unwrap().into(),
+        let span = t.span.to(&casted.span);
+        return Ok(Expr::new(ExprKind::Tcast(
+            t.tok.as_primtype().unwrap().into(),
             Box::new(casted)
-        ));
+        ), span));
     }
 
-    fn fn_acc_ex(&mut self) -> StrRes<Expr>
+    fn fn_acc_ex(&mut self) -> ParsRes<Expr>
     {
         let mut e = self.nucle()?;
         loop {
             let Some(t) = self.peek() else {
                 break;
             };
-            match t.0.typ() {
+            let lo = e.span;
+            let kind = match t.tok.typ() {
                 TokTyp::Dollar => {
                     self.advance(); // $
                     let i = self.consume_ident()?;
-                    e = Expr::TblFd(Box::new(e),
+                    ExprKind::TblFd(Box::new(e),
                         Rc::new(i.try_into().unwrap()),
-                    );
+                    )
                 },
                 TokTyp::Hash => {
                     self.advance(); // #
                     let args = self.comma_ex(TokTyp::Semic)?;
-                    e = Expr::Fcall(Box::new(e), args);
+                    ExprKind::Fcall(Box::new(e), args)
                 },
                 TokTyp::HashDollar => {
                     self.advance(); // #$
                     let i = self.consume_ident()?;
                     self.exp_adv(TokTyp::Hash)?; // #
                     let args = self.comma_ex(TokTyp::Semic)?;
-                    e = Expr::TbFcl(Box::new(e),
+                    ExprKind::TbFcl(Box::new(e),
                         Rc::new(i.try_into().unwrap()),
-                        args);
+                        args)
                 },
                 _ => break,
-            }
+            };
+            e = Expr::new(kind, self.since(lo));
         }
         return Ok(e);
     }
 
-    fn nucle(&mut self) -> StrRes<Expr>
+    fn nucle(&mut self) -> ParsRes<Expr>
     {
-        const MSG: &str = "(, #, !, _, $, \\[, \\#, ident or literal";
         let Some(tok) = self.peek() else {
-            return eof_err!(MSG);
+            return Err(self.error(ErrKind::ExpectedExpr, None));
         };
-        match tok.0.typ() {
-            TokTyp::Lparen => self.parented(),
-            TokTyp::Hash => self.func(tok.1),
-            TokTyp::BsLsb => self.if_expr(),
-            TokTyp::BsHash => self.short_fn(tok.1),
+        let line = tok.span.line;
+        let kind = match tok.tok.typ() {
+            TokTyp::Lparen => return self.parented(),
+            TokTyp::Hash => self.func(line)?,
+            TokTyp::BsLsb => self.if_expr()?,
+            TokTyp::BsHash => self.short_fn(line)?,
             TokTyp::RecF => {
                 self.advance();
-                Ok(Expr::RecFn)
+                ExprKind::RecFn
             },
-            TokTyp::Bang => self.proc(tok.1),
+            TokTyp::Bang => self.proc(line)?,
             TokTyp::RecP => {
                 self.advance();
-                Ok(Expr::RecPc)
+                ExprKind::RecPc
             },
-            TokTyp::Uscore =>     self.arrlit(),
-            TokTyp::Dollar =>     self.tbllit(),
+            TokTyp::Uscore =>     self.arrlit()?,
+            TokTyp::Dollar =>     self.tbllit()?,
             TokTyp::RecT => {
                 self.advance();
-                Ok(Expr::RecsT(tok.0.as_rect().unwrap()))
+                ExprKind::RecsT(tok.tok.as_rect().unwrap())
             },
             TokTyp::Ident => {
                 self.advance();
-                let id = tok.0.as_ident().unwrap();
-                return Ok(Expr::Ident(Rc::new(
-                    id.try_into().unwrap()
-                )));
+                let id = tok.tok.as_ident().unwrap();
+                ExprKind::Ident(Rc::new(id.try_into().unwrap()))
             },
             // literals
-            TokTyp::ValV => {self.advance(); Ok(Expr::Const(Val::V))},
-            TokTyp::ValB => Ok(self.valb(tok.0.as_valb().unwrap())),
-            TokTyp::ValC => Ok(self.valc(tok.0.as_valc().unwrap())),
-            TokTyp::ValN => Ok(self.valn(tok.0.as_valn().unwrap())),
-            TokTyp::ValZ => Ok(self.valz(tok.0.as_valz().unwrap())),
-            TokTyp::ValR => Ok(self.valr(tok.0.as_valr().unwrap())),
-            TokTyp::String =>  self.string(tok.0.as_string().unwrap()),
-            _ => exp_err!(MSG, tok),
-        }
+            TokTyp::ValV => {self.advance(); ExprKind::Const(Val::V)},
+            TokTyp::ValB => self.valb(tok.tok.as_valb().unwrap()),
+            TokTyp::ValC => self.valc(tok.tok.as_valc().unwrap()),
+            TokTyp::ValN => self.valn(tok.tok.as_valn().unwrap()),
+            TokTyp::ValZ => self.valz(tok.tok.as_valz().unwrap()),
+            TokTyp::ValR => self.valr(tok.tok.as_valr().unwrap()),
+            TokTyp::String =>  self.string(tok.tok.as_string().unwrap())?,
+            TokTyp::Unknown =>
+                return Err(self.error(ErrKind::UnknownToken, Some(tok))),
+            _ => return Err(self.error(ErrKind::ExpectedExpr, Some(tok))),
+        };
+        return Ok(Expr::new(kind, self.since(tok.span)));
     }
 
     valx_fn!(valb, B, bool);
@@ -606,35 +747,35 @@ impl<'src> Nip<'src>
 
     // parses comma separated exprs which end in a specific token
     // it also consumes þe end token, so no need to exp_adv after
-    fn comma_ex(&mut self, end: TokTyp) -> StrRes<Vec<Expr>>
+    fn comma_ex(&mut self, end: TokTyp) -> ParsRes<Vec<Expr>>
     {
         // check empty
         if self.matches(end) {
             self.advance(); // end
             return Ok(vec![]);
         }
-        let comma_or_end = format!(", or {end:?}");
+        let comma_or_end = ErrKind::Expected(vec![TokTyp::Comma, end]);
         let mut exs = vec![];
         loop {
             let ex = self.expr()?;
             exs.push(ex);
             let Some(tok) = self.peek() else {
-                return eof_err!(comma_or_end);
+                return Err(self.error(comma_or_end, None));
             };
-            let tt = tok.0.typ();
+            let tt = tok.tok.typ();
             if tt == end {
                 self.advance(); // consume end
                 return Ok(exs);
             }
             if tt != TokTyp::Comma {
-                return exp_err!(comma_or_end, tok);
+                return Err(self.error(comma_or_end, Some(tok)));
             }
             self.advance();
         }
     }
 
     // called when (
-    fn parented(&mut self) -> StrRes<Expr>
+    fn parented(&mut self) -> ParsRes<Expr>
     {
         self.advance(); // (
         let e = self.expr()?;
@@ -643,27 +784,36 @@ impl<'src> Nip<'src>
     }
 
     // called when _
-    fn arrlit(&mut self) -> StrRes<Expr>
+    fn arrlit(&mut self) -> ParsRes<ExprKind>
     {
         self.advance(); // _
+        // typed empty, like _N%;
+        let pt = self.peek().and_then(|t| t.tok.as_primtype());
+        let semic = self.peekn::<1>()
+            .is_some_and(|t| t.tok.typ() == TokTyp::Semic);
+        if let (Some(pt), true) = (pt, semic) {
+            self.advance(); // þe primtype
+            self.advance(); // ;
+            let a = Array::with_type(&pt.into());
+            return Ok(ExprKind::Const(Val::from_array(a)));
+        }
         let arr_e = self.comma_ex(TokTyp::Semic)?;
-        return Ok(Expr::Array(arr_e));
+        return Ok(ExprKind::Array(arr_e));
     }
 
     // called when $
-    fn tbllit(&mut self) -> StrRes<Expr>
+    fn tbllit(&mut self) -> ParsRes<ExprKind>
     {
-        const MSG: &str = "Ident or ;";
         self.advance(); // $
         let mut tbl_e = vec![];
         loop {
             let Some(t) = self.peek() else {
-                return eof_err!(MSG);
+                return eof_err!(self, [Ident, Semic]);
             };
-            match t.0.typ() {
+            match t.tok.typ() {
                 TokTyp::Ident => {}, // ok, continue reading
                 TokTyp::Semic => break,
-                _ => return exp_err!(MSG, t),
+                _ => return exp_err!(self, [Ident, Semic], t),
             }
             let i = self.consume_ident()?;
             self.exp_adv(TokTyp::Equal)?;
@@ -673,27 +823,27 @@ impl<'src> Nip<'src>
             tbl_e.push((i, e));
         }
         self.advance(); // ;
-        Ok(Expr::Table(tbl_e))
+        Ok(ExprKind::Table(tbl_e))
     }
 
     // called when #
-    fn func(&mut self, line: usize) -> StrRes<Expr>
+    fn func(&mut self, line: usize) -> ParsRes<ExprKind>
     {
         self.subr(line, SubrType::F)
     }
 
     // called when !
-    fn proc(&mut self, line: usize) -> StrRes<Expr>
+    fn proc(&mut self, line: usize) -> ParsRes<ExprKind>
     {
         self.subr(line, SubrType::P)
     }
 
     // helper for func & proc
-    fn subr(&mut self, line: usize, st: SubrType) -> StrRes<Expr>
+    fn subr(&mut self, line: usize, st: SubrType) -> ParsRes<ExprKind>
     {
         self.advance(); // # or !
         let name = match self.peek() { // FIXME: maybe use map?
-            Some((t, _)) => t.as_string().map(
+            Some(t) => t.tok.as_string().map(
                 |s| Rc::new(s.try_into().unwrap())
             ),
             None => None,
@@ -709,7 +859,7 @@ impl<'src> Nip<'src>
             .iter()
             .map(|b| Rc::new(b.try_into().unwrap()))
             .collect();
-        let bloq = self.block()?;
+        let bloq = self.block();
         self.exp_adv(TokTyp::Period)?;
         let meta = SubrMeta { line: line, name: name };
         let subr = Subr {
@@ -720,13 +870,13 @@ impl<'src> Nip<'src>
         };
         let mrs = Rc::new(RefCell::new(subr));
         return Ok(match st {
-            SubrType::F => Expr::FnDef(mrs),
-            SubrType::P => Expr::PcDef(mrs),
+            SubrType::F => ExprKind::FnDef(mrs),
+            SubrType::P => ExprKind::PcDef(mrs),
         });
     }
 
     // matches (Ident (Comma Ident)*)? END
-    fn pars(&mut self, end: TokTyp) -> StrRes<Vec<&[u8]>>
+    fn pars(&mut self, end: TokTyp) -> ParsRes<Vec<&[u8]>>
     {
         let mut res: Vec<&[u8]> = vec![];
         if self.matches(end) {
@@ -747,7 +897,7 @@ impl<'src> Nip<'src>
 
     // called when \#
     #[inline]
-    fn short_fn(&mut self, line: usize) -> StrRes<Expr>
+    fn short_fn(&mut self, line: usize) -> ParsRes<ExprKind>
     {
         self.advance(); // \#
         // TODO: maybe put actual name of short functions?
@@ -756,61 +906,77 @@ impl<'src> Nip<'src>
             .map(|b| Rc::new(b.try_into().unwrap()))
             .collect();
         let ret_expr = self.expr()?;
+        let span = ret_expr.span;
         self.exp_adv(TokTyp::Period)?;
         let meta = SubrMeta { line: line, name: None };
         let subr = Subr {
             meta: meta,
             upvs: vec![],
             pars: pars,
-            body: vec![Stmt::Return(ret_expr)],
+            body: vec![Stmt::new(StmtKind::Return(ret_expr), span)],
         };
         let mrs = Rc::new(RefCell::new(subr));
-        return Ok(Expr::FnDef(mrs));
+        return Ok(ExprKind::FnDef(mrs));
     }
 
     // called when \[
     #[inline]
-    fn if_expr(&mut self) -> StrRes<Expr>
+    fn if_expr(&mut self) -> ParsRes<ExprKind>
     {
         self.advance(); // \[
         let mut cases = vec![];
         loop {
-            if self.matches(TokTyp::Then) {
-                todo!("final else =>");
+            // þe default, also wiþout `=>`, as in `[c => a; b]`
+            if !cases.is_empty() && self.matches(TokTyp::Then) {
+                self.advance(); // =>
+                let e = self.expr()?;
+                self.exp_adv(TokTyp::RsqBra)?;
+                return Ok(ExprKind::IfExp(cases, Box::new(e)));
+            }
+            if !cases.is_empty() && self.matches(TokTyp::RsqBra) {
+                return Err(self.error(ErrKind::NoDefault, self.peek()));
             }
             let e = self.expr()?;
             if !cases.is_empty() && self.matches(TokTyp::RsqBra) {
                 self.advance(); // ]
-                return Ok(Expr::IfExp(cases, Box::new(e)));
+                return Ok(ExprKind::IfExp(cases, Box::new(e)));
             }
             if self.exp_adv(TokTyp::Then).is_err() {
-                let msg = if cases.is_empty() {"=>"} else {"=> or ]"};
-                return exp_err!(msg, self.peek().unwrap());
+                let tok = self.peek().unwrap();
+                return if cases.is_empty() {
+                    exp_err!(self, [Then], tok)
+                } else {
+                    exp_err!(self, [Then, RsqBra], tok)
+                };
             }
             let f = self.expr()?;
+            if self.matches(TokTyp::RsqBra) {
+                return Err(self.error(ErrKind::NoDefault, self.peek()));
+            }
             self.exp_adv(TokTyp::Semic)?;
             cases.push((e, f));
         }
     }
 
-    fn consume_ident(&mut self) -> StrRes<&'src [u8]>
+    fn consume_ident(&mut self) -> ParsRes<&'src [u8]>
     {
         let Some(tok) = self.peek() else {
-            return eof_err!("Ident");
+            return eof_err!(self, [Ident]);
         };
-        let Some(i) = tok.0.as_ident() else {
-            return exp_err!("Ident", tok);
+        let Some(i) = tok.tok.as_ident() else {
+            return exp_err!(self, [Ident], tok);
         };
         self.advance(); // ident
         return Ok(i);
     }
 
     // called when curr tok is String
-    fn string(&mut self, b: &[u8]) -> StrRes<Expr>
+    fn string(&mut self, b: &[u8]) -> ParsRes<ExprKind>
     {
-        let a = Array::try_from(b)?;
+        let a = Array::try_from(b).map_err(|e|
+            self.error(ErrKind::BadLiteral(e.into()), self.peek()))?;
         self.advance();
-        return Ok(Expr::Const(Val::from_array(a)));
+        return Ok(ExprKind::Const(Val::from_array(a)));
     }
 }
 
@@ -1,7 +1,7 @@
 /* parsnip/toki.rs */
 
 use num_enum;
-use crate::util;
+use crate::{asterix::Span, util};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[derive(num_enum::TryFromPrimitive)]
@@ -26,7 +26,13 @@ impl TryFrom<&u8> for PrimType
     }
 }
 
-pub type LnToken<'a> = (Token<'a>, usize);
+// a token & where it was found
+#[derive(Copy, Clone)]
+pub struct LnToken<'src>
+{
+    pub tok:  Token<'src>,
+    pub span: Span,
+}
 
 #[derive(Copy, Clone)]
 pub struct Token<'src>
@@ -44,19 +50,19 @@ macro_rules! new_tok {
 
 pub(crate) use new_tok;
 
+// Err if þe number doesn't fit
 macro_rules! parse_fn {
     ($fn_name:ident, $val_type:ty, $toktyp:ident) => {
-        pub fn $fn_name(s: &'src [u8]) -> Self {
-            return Self {
+        pub fn $fn_name(s: &'src [u8]) -> Result<Self, String> {
+            let v = std::str::from_utf8(s)
+                .unwrap()
+                .parse::<$val_type>()
+                .map_err(|e| e.to_string())?;
+            return Ok(Self {
                 typ: TokTyp::$toktyp,
-                val: TokVal {
-                    $toktyp: std::str::from_utf8(s)
-                        .unwrap()
-                        .parse::<$val_type>()
-                        .unwrap()
-                },
+                val: TokVal {$toktyp: v},
                 lex: s,
-            };
+            });
         }
     };
 }
@@ -157,7 +163,8 @@ impl<'src> Token<'src>
             b'|' => Vbar,       b'~' => Tilde,      b'=' => Equal,
             b'#' => Hash,       b'!' => Bang,       b'@' => AtSign,
             b'[' => LsqBra,     b']' => RsqBra,     b'_' => Uscore,
-            b'.' => Period,     b',' => Comma,      b':' => Colon;
+            b'.' => Period,     b',' => Comma,      b':' => Colon,
+            b'^' => Caret;
             _ => util::format_err!(
                 "unknown single char token {}", char::from(s[0])
             )
@@ -274,6 +281,8 @@ pub enum TokTyp
 //    Bang2,
     LsqBra2,
     RsqBra2,
+    Langle2,    // <<
+    Rangle2,    // >>
     // 2 different char
     Ne,         // ~=
     Ge,         // >=
@@ -289,6 +298,7 @@ pub enum TokTyp
     DotAt,      // .@
     DotHash,    // .#
     DotBang,    // .!
+    AstCaret,   // *^
     // literals
     ValV,
     ValB,
@@ -309,6 +319,88 @@ pub enum TokTyp
     Eof,
 }
 
+impl std::fmt::Display for TokTyp
+{
+    // for error messages: symbols between backquotes, oþers in words
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error>
+    {
+        let sym = match self {
+            Self::Plus       => "+",
+            Self::Minus      => "-",
+            Self::Asterisk   => "*",
+            Self::Slash      => "/",
+            Self::Bslash     => "\\",
+            Self::And        => "&",
+            Self::Vbar       => "|",
+            Self::Caret      => "^",
+            Self::Dollar     => "$",
+            Self::Tilde      => "~",
+            Self::Equal      => "=",
+            Self::Uscore     => "_",
+            Self::Period     => ".",
+            Self::Comma      => ",",
+            Self::Colon      => ":",
+            Self::Semic      => ";",
+            Self::Hash       => "#",
+            Self::Bang       => "!",
+            Self::AtSign     => "@",
+            Self::Lparen     => "(",
+            Self::Rparen     => ")",
+            Self::LsqBra     => "[",
+            Self::RsqBra     => "]",
+            Self::Lbrace     => "{",
+            Self::Rbrace     => "}",
+            Self::Langle     => "<",
+            Self::Rangle     => ">",
+            Self::Plus2      => "++",
+            Self::Minus2     => "--",
+            Self::Asterisk2  => "**",
+            Self::Slash2     => "//",
+            Self::Bslash2    => "\\\\",
+            Self::And2       => "&&",
+            Self::Vbar2      => "||",
+            Self::Caret2     => "^^",
+            Self::Tilde2     => "~~",
+            Self::Equal2     => "==",
+            Self::LsqBra2    => "[[",
+            Self::RsqBra2    => "]]",
+            Self::Langle2    => "<<",
+            Self::Rangle2    => ">>",
+            Self::Ne         => "~=",
+            Self::Ge         => ">=",
+            Self::Le         => "<=",
+            Self::Then       => "=>",
+            Self::HashDollar => "#$",
+            Self::BangDollar => "!$",
+            Self::BsLsb      => "\\[",
+            Self::BsHash     => "\\#",
+            Self::AndQu      => "&?",
+            Self::VbarQu     => "|?",
+            Self::AtSign2    => "@@",
+            Self::DotAt      => ".@",
+            Self::DotHash    => ".#",
+            Self::DotBang    => ".!",
+            Self::AstCaret   => "*^",
+            Self::ValV       => "V",
+            Self::RecT       => "$@",
+            Self::RecF       => "#@",
+            Self::RecP       => "!@",
+            Self::ValB       => return write!(f, "B% literal"),
+            Self::ValC       => return write!(f, "C% literal"),
+            Self::ValN       => return write!(f, "N% literal"),
+            Self::ValZ       => return write!(f, "Z% literal"),
+            Self::ValR       => return write!(f, "R% literal"),
+            Self::String     => return write!(f, "string"),
+            Self::Ident      => return write!(f, "identifier"),
+            Self::PrimType   => return write!(f, "type%"),
+            Self::Comment    => return write!(f, "comment"),
+            Self::Unknown    => return write!(f, "unknown token"),
+            Self::Eof        => return write!(f, "end of file"),
+        };
+        write!(f, "`{sym}`")
+    }
+}
+
 #[allow(non_snake_case)] // to reuse þe enum & union fields
 #[derive(Clone, Copy)]
 pub union TokVal<'src>
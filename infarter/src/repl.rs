@@ -0,0 +1,149 @@
+/* repl.rs */
+
+// read-eval-print loop on top of tarzan, keeping one Scope alive
+
+use std::io::{BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use crate::{
+    asterix::Block,
+    parsnip::{self, ParsnipError},
+    semanal,
+    tarzan::Scope,
+    util::{self, StrRes},
+};
+
+const HELP: &str = "\
+Type DryFart statements (ended by `.`) or bare expressions to see þeir value.
+  :scope        print all variables in scope
+  :load <file>  run a source file in þe current scope
+  :help         show þis message
+  :quit         exit (or press Ctrl-D)";
+
+pub fn repl() -> StrRes<()>
+{
+    // semanal still panics on errors, þat must not kill þe session
+    panic::set_hook(Box::new(|info| {
+        let p = info.payload();
+        let msg = p.downcast_ref::<&str>().copied()
+            .or_else(|| p.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown error");
+        eprintln!("{msg}");
+    }));
+    let mut dry = Repl {
+        scope: Scope::new(),
+        check: semanal::Incremental::default(),
+    };
+    println!("DryFart REPL, :help for help");
+    let mut buf = String::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        prompt(if buf.is_empty() {"df> "} else {"..> "});
+        let Some(line) = lines.next() else {
+            println!();
+            break;
+        };
+        let line = line.map_err(|e| format!("Could not read stdin: {e}"))?;
+        if buf.is_empty() {
+            match line.trim().split_once(' ').unwrap_or((line.trim(), "")) {
+                (":quit" | ":q", _) => break,
+                (":help" | ":h", _) => println!("{HELP}"),
+                (":scope", _) => dry.scope.print(),
+                (":load", f) => {
+                    if let Err(e) = dry.guard(|d| d.load(f.trim())) {
+                        eprintln!("{e}");
+                    }
+                },
+                (m, _) if m.starts_with(':') =>
+                    eprintln!("unknown command `{m}`, try :help"),
+                ("", _) => {},
+                _ => buf = line,
+            }
+        } else {
+            buf.push('\n');
+            buf.push_str(&line);
+        }
+        if buf.is_empty() {
+            continue;
+        }
+        match dry.guard(|d| d.input(&buf)) {
+            Ok(Some(false)) => {}, // incomplete, keep reading
+            Ok(_) => buf.clear(), // done or panicked
+            Err(e) => {
+                eprintln!("{e}");
+                buf.clear();
+            },
+        }
+    }
+    let _ = panic::take_hook();
+    return Ok(());
+}
+
+fn prompt(p: &str)
+{
+    print!("{p}");
+    let _ = std::io::stdout().flush();
+}
+
+struct Repl
+{
+    scope: Scope,
+    check: semanal::Incremental,
+}
+
+impl Repl
+{
+    // Ok(false) if `taco` is not complete yet
+    fn input(&mut self, taco: &str) -> StrRes<bool>
+    {
+        if let Ok(mut ex) = parsnip::parse_expr(taco.to_owned()) {
+            self.check.check_expr(&mut ex);
+            let v = self.scope.eval_expr(&ex).map_err(|e| e.to_string())?;
+            println!("{v}");
+            return Ok(true);
+        }
+        match parsnip::parse(taco.to_owned()) {
+            Ok(b) => self.exec(b)?,
+            Err(e) if e.iter().all(ParsnipError::at_eof) => return Ok(false),
+            Err(e) => return Err(parsnip::render_all(&e, taco, "<repl>")),
+        }
+        return Ok(true);
+    }
+
+    fn load(&mut self, fname: &str) -> StrRes<()>
+    {
+        if fname.is_empty() {
+            return util::format_err!("usage: :load <file>");
+        }
+        let taco = std::fs::read_to_string(fname)
+            .map_err(|e| format!("Could not read {fname}: {e}"))?;
+        let b = parsnip::parse(taco.clone())
+            .map_err(|e| parsnip::render_all(&e, &taco, fname))?;
+        return self.exec(b);
+    }
+
+    fn exec(&mut self, mut b: Block) -> StrRes<()>
+    {
+        self.check.check(&mut b);
+        return match self.scope.do_top_block(&b) {
+            Ok(None) => Ok(()),
+            Err(e) => Err(e.to_string()),
+            Ok(Some(_)) => util::format_err!(
+                "cannot return, exit or break at top level"),
+        };
+    }
+
+    // runs `f`, catching any panic (Ok(None)); if it fails eiþer way, undoes
+    // þe variables & þe semantic analysis of þe input, so þey still agree
+    fn guard<T, F>(&mut self, f: F) -> StrRes<Option<T>>
+    where F: FnOnce(&mut Self) -> StrRes<T>
+    {
+        let saved = (self.check.clone(), self.scope.clone());
+        let res = match panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(Ok(v)) => return Ok(Some(v)),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Ok(None), // þe hook has already printed þe message
+        };
+        (self.check, self.scope) = saved;
+        return res;
+    }
+}
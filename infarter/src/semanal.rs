@@ -14,6 +14,29 @@ pub fn check(b: &mut Block)
     upv_check(b);
 }
 
+// same as check, but remembers þe variables declared in previous calls
+// so þat þe REPL can check one statement at a time
+#[derive(Debug, Default, Clone)]
+pub struct Incremental
+{
+    ua: UpvAnal,
+}
+
+impl Incremental
+{
+    pub fn check(&mut self, b: &mut Block)
+    {
+        std_check_block(b);
+        self.ua.pass_block(b);
+    }
+
+    pub fn check_expr(&mut self, e: &mut Expr)
+    {
+        std_check_expr(e);
+        self.ua.pass_expr(e);
+    }
+}
+
 // changes STD idents to the constant table STD
 pub fn std_check_block(b: &mut Block)
 {
@@ -24,15 +47,15 @@ pub fn std_check_block(b: &mut Block)
 
 fn std_check_stmt(s: &mut Stmt)
 {
-    match s {
-        Stmt::Assign(x, e)    => {std_check_expr(x); std_check_expr(e);},
-        Stmt::OperOn(l, _, e) => {std_check_expr(l); std_check_expr(e);},
-        Stmt::IfElse(i, o, e) => {
+    match &mut s.kind {
+        StmtKind::Assign(x, e)    => {std_check_expr(x); std_check_expr(e);},
+        StmtKind::OperOn(l, _, e) => {std_check_expr(l); std_check_expr(e);},
+        StmtKind::IfElse(i, o, e) => {
             std_check_ifcase(i);
             for c in o {std_check_ifcase(c);}
             if let Some(m) = e {std_check_block(m);}
         },
-        Stmt::Switch(m, c, d) => {
+        StmtKind::Switch(m, c, d) => {
             std_check_expr(m);
             for x in c {
                 std_check_expr(&mut x.comp);
@@ -40,9 +63,9 @@ fn std_check_stmt(s: &mut Stmt)
             }
             std_check_block(d);
         },
-        Stmt::LoopIf(l) => std_check_loop(l),
-        Stmt::Return(e) => std_check_expr(e),
-        Stmt::PcCall(p, a) => {
+        StmtKind::LoopIf(l) => std_check_loop(l),
+        StmtKind::Return(e) => std_check_expr(e),
+        StmtKind::PcCall(p, a) => {
             std_check_expr(p);
             for e in a { std_check_expr(e); }
         },
@@ -72,25 +95,25 @@ fn std_check_loop(l: &mut Loop)
 
 fn std_check_expr(e: &mut Expr)
 {
-    match e {
-        Expr::Ident(i) => if i.as_bytes() == b"STD" {
-            *e = Expr::Const(Val::from(NatTb::STD));
+    match &mut e.kind {
+        ExprKind::Ident(i) => if i.as_bytes() == b"STD" {
+            e.kind = ExprKind::Const(Val::from(NatTb::STD));
         },
-        Expr::Tcast(_, b) => std_check_expr(b),
-        Expr::BinOp(f, _, g) => {std_check_expr(f); std_check_expr(g);},
-        Expr::UniOp(e, _) => std_check_expr(e),
-        Expr::CmpOp(e, v) => {
+        ExprKind::Tcast(_, b) => std_check_expr(b),
+        ExprKind::BinOp(f, _, g) => {std_check_expr(f); std_check_expr(g);},
+        ExprKind::UniOp(e, _) => std_check_expr(e),
+        ExprKind::CmpOp(e, v) => {
             std_check_expr(e);
             for (_, f) in v {
                 std_check_expr(f);
             }
         },
-        Expr::FnDef(s) |
-        Expr::PcDef(s) => std_check_block(&mut s.borrow_mut().body),
-        Expr::Fcall(f, a) => {std_check_expr(f); std_check_expr_vec(a);},
-        Expr::Array(a) => std_check_expr_vec(a),
-        Expr::Table(v) => for (_, e) in v {std_check_expr(e);},
-        Expr::TblFd(t, _) => std_check_expr(t),
+        ExprKind::FnDef(s) |
+        ExprKind::PcDef(s) => std_check_block(&mut s.borrow_mut().body),
+        ExprKind::Fcall(f, a) => {std_check_expr(f); std_check_expr_vec(a);},
+        ExprKind::Array(a) => std_check_expr_vec(a),
+        ExprKind::Table(v) => for (_, e) in v {std_check_expr(e);},
+        ExprKind::TblFd(t, _) => std_check_expr(t),
         _ => {},
     }
 }
@@ -114,7 +137,7 @@ fn upv_check(b: &mut Block)
 
 type DfStrAccum = util::ArraySet<Rc<DfStr>>;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct UpvEnv
 {
     // declared variables (all)
@@ -151,7 +174,7 @@ impl UpvEnv
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct UpvAnal
 {
     // all previous scopes
@@ -188,22 +211,22 @@ impl UpvAnal
 
     fn pass_stmt(&mut self, s: &mut Stmt)
     {
-        match s {
-            Stmt::Assign(a, e)    => self.pass_s_assign(a, e),
-            Stmt::OperOn(a, _, e) => self.pass_s_operon(a, e),
-            Stmt::IfElse(i, o, e) => {
+        match &mut s.kind {
+            StmtKind::Assign(a, e)    => self.pass_s_assign(a, e),
+            StmtKind::OperOn(a, _, e) => self.pass_s_operon(a, e),
+            StmtKind::IfElse(i, o, e) => {
                 self.pass_ifcase(i);
                 for c in o {self.pass_ifcase(c);}
                 if let Some(b) = e {self.pass_block(b);}
             },
-            Stmt::Switch(m, c, d) => {
+            StmtKind::Switch(m, c, d) => {
                 self.pass_expr(m);
                 for x in c {self.pass_swcase(x);}
                 self.pass_block(d);
             },
-            Stmt::LoopIf(l) => self.pass_loop(l),
-            Stmt::Return(e) => self.pass_expr(e),
-            Stmt::PcCall(p, a) => {
+            StmtKind::LoopIf(l) => self.pass_loop(l),
+            StmtKind::Return(e) => self.pass_expr(e),
+            StmtKind::PcCall(p, a) => {
                 self.pass_expr(p);
                 for x in a {self.pass_expr(x);}
             },
@@ -214,9 +237,9 @@ impl UpvAnal
     fn pass_s_assign(&mut self, a: &mut Expr, e: &mut Expr)
     {
         self.pass_expr(e);
-        match a {
+        match &mut a.kind {
             // þe only special case is þe simple assign
-            Expr::Ident(i) => self.pass_ass_var(i),
+            ExprKind::Ident(i) => self.pass_ass_var(i),
             _ => self.pass_expr(a),
         }
     }
@@ -258,35 +281,36 @@ impl UpvAnal
 
     fn pass_expr(&mut self, e: &mut Expr)
     {
-        match e {
-            Expr::Ident(i) => { // þis is important
+        let span = e.span;
+        match &mut e.kind {
+            ExprKind::Ident(i) => { // þis is important
                 if !self.curr.has_var(i) {
-                    self.try_upv_idf(i);
+                    self.try_upv_idf(i, span);
                 }
             },
-            Expr::Tcast(_, e) |
-            Expr::UniOp(e, _) => self.pass_expr(e),
-            Expr::BinOp(a, _, b) => {
+            ExprKind::Tcast(_, e) |
+            ExprKind::UniOp(e, _) => self.pass_expr(e),
+            ExprKind::BinOp(a, _, b) => {
                 self.pass_expr(a);
                 self.pass_expr(b);
             },
-            Expr::CmpOp(e, v) => {
+            ExprKind::CmpOp(e, v) => {
                 self.pass_expr(e);
                 for (_, x) in v {self.pass_expr(x);}
             },
             // þis 1 is important
-            Expr::FnDef(s) |
-            Expr::PcDef(s) => self.pass_subr(&mut s.borrow_mut()),
+            ExprKind::FnDef(s) |
+            ExprKind::PcDef(s) => self.pass_subr(&mut s.borrow_mut()),
             // continue normal
-            Expr::Fcall(f, a) => {
+            ExprKind::Fcall(f, a) => {
                 self.pass_expr(f);
                 self.pass_expr_vec(a);
             },
-            Expr::Array(a) => self.pass_expr_vec(a),
+            ExprKind::Array(a) => self.pass_expr_vec(a),
             // þis will be important when eking $@ captures
-            Expr::Table(t) => for (_, e) in t {self.pass_expr(e);},
-            Expr::TblFd(t, _) => self.pass_expr(t),
-            Expr::IfExp(c, e) => {
+            ExprKind::Table(t) => for (_, e) in t {self.pass_expr(e);},
+            ExprKind::TblFd(t, _) => self.pass_expr(t),
+            ExprKind::IfExp(c, e) => {
                 for (a, b) in c {
                     self.pass_expr(a);
                     self.pass_expr(b);
@@ -316,13 +340,15 @@ impl UpvAnal
     }
 
     #[inline]
-    fn try_upv_idf(&mut self, i: &Rc<DfStr>)
+    fn try_upv_idf(&mut self, i: &Rc<DfStr>, span: Span)
     {
         // try to resolve `i` by looking into all parent UpvEnvs
-        let lev = self.pres
+        let Some(lev) = self.pres
             .iter() // from þe innermost
             .position(|env| env.has_var(i))
-            .expect(&format!("could not resolve name `{i}`"));
+        else {
+            panic!("could not resolve name `{i}` at {span}");
+        };
         // add `i` as upvalue to all UpvEnvs from current till level
         self.curr.new_upv(i);
         for c in self.pres.iter_mut_till(lev) {
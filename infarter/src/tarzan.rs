@@ -1,25 +1,135 @@
 /* tarzan.rs */
 
-use std::rc::Rc;
+use std::{rc::Rc, fmt};
 use crate::{
     asterix::*,
+    dflib,
     util,
     util::{MutRc, DfStr},
 };
 
 /* MAIN FUNCTION to execute all þe programm */
-pub fn exec_main(prog: &Block)
+// `wrap`: N% & Z% overflows wrap around instead of being errors
+pub fn exec_main(prog: &Block, wrap: bool) -> Result<(), String>
 {
-    if exec_block(prog).is_some() {
-        panic!("ERROR: at main script: cannot return, exit or break");
+    match exec_block(prog, wrap) {
+        Ok(None) => return Ok(()),
+        Ok(Some(_)) => return util::format_err!(
+            "RuntimeError: cannot return, exit or break at main script"),
+        Err(e) => return Err(e.to_string()),
     }
 }
 
+pub type RtRes<T> = Result<T, RuntimeError>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RtErrKind
+{
+    Name,     // unknown variable
+    Type,     // wrong operand types, calling a non-subroutine...
+    Arity,    // wrong number of args
+    Index,    // negative or out of bounds
+    Field,    // table wiþout þat field
+    Cast,     // impossible type cast
+    Arith,    // integer overflow or modulo by zero
+    Equality, // == or ~= between R%
+    Control,  // return, exit or break where þey can't be
+    Lib,      // from a STD subroutine
+}
+
+impl fmt::Display for RtErrKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let s = match self {
+            Self::Name     => "NameError",
+            Self::Type     => "TypeError",
+            Self::Arity    => "ArityError",
+            Self::Index    => "IndexError",
+            Self::Field    => "FieldError",
+            Self::Cast     => "CastError",
+            Self::Arith    => "ArithError",
+            Self::Equality => "EqualityError",
+            Self::Control  => "ControlError",
+            Self::Lib      => "LibError",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeError
+{
+    pub kind:  RtErrKind,
+    pub msg:   String,
+    pub span:  Option<Span>,  // of þe innermost expr or stmt
+    pub trace: Vec<SubrMeta>, // subroutines, from þe innermost
+}
+
+impl RuntimeError
+{
+    pub fn new(kind: RtErrKind, msg: String) -> Self
+    {
+        Self { kind: kind, msg: msg, span: None, trace: vec![] }
+    }
+
+    // only þe first (innermost) span is kept
+    #[must_use]
+    fn at(mut self, span: Span) -> Self
+    {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+        return self;
+    }
+
+    #[must_use]
+    fn inside(mut self, meta: &SubrMeta) -> Self
+    {
+        self.trace.push(meta.clone());
+        return self;
+    }
+}
+
+// errors from natives
+impl From<String> for RuntimeError
+{
+    fn from(msg: String) -> Self
+    {
+        Self::new(RtErrKind::Lib, msg)
+    }
+}
+
+impl fmt::Display for RuntimeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "RuntimeError: {}: {}", self.kind, self.msg)?;
+        if let Some(s) = self.span {
+            write!(f, " at {s}")?;
+        }
+        for m in &self.trace {
+            match &m.name {
+                Some(n) => write!(f, "\n  in `{n}`")?,
+                None => write!(f, "\n  in anonymous subroutine")?,
+            }
+            write!(f, " defined at line {}", m.line)?;
+        }
+        write!(f, "\n  in main script")
+    }
+}
+
+macro_rules! rt_err {
+    ($kind:ident, $($args:expr),+) => (
+        Err(RuntimeError::new(RtErrKind::$kind, format!($($args),+)))
+    )
+}
+
 macro_rules! do_loop_block {
     ($zelf:expr, $block:expr) => {
-        if let Some(ba) = $zelf.no_env_block($block) {
+        if let Some(ba) = $zelf.no_env_block($block)? {
             let BlockAction::Loo(0, is_again) = ba else {
-                return ba.exiting_loop();
+                return Ok(ba.exiting_loop());
             };
             if is_again {
                 continue;
@@ -30,28 +140,55 @@ macro_rules! do_loop_block {
     }
 }
 
-fn exec_block(b: &Block) -> Option<BlockAction>
+fn exec_block(b: &Block, wrap: bool) -> RtRes<Option<BlockAction>>
 {
     let mut scope = Scope::new();
+    scope.wrap = wrap;
     return scope.do_block(b);
 }
 
+#[derive(Clone)]
 pub struct Scope
 {
     vars: util::VecMap<Rc<DfStr>, Val>,
     callee: Option<Val>, // main (None), func or proc
+    wrap: bool,          // wrapping integer ariþmetic, inherited by calls
+}
+
+impl Default for Scope
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+// for STD$a$map & such
+impl dflib::Caller for Scope
+{
+    type Error = RuntimeError;
+
+    fn call_func(&self, f: &Val, args: Vec<Val>) -> RtRes<Val>
+    {
+        return Scope::call_func(self, f, args);
+    }
+
+    fn call_proc(&self, p: &Val, args: Vec<Val>) -> RtRes<()>
+    {
+        return Scope::call_proc(self, p, args);
+    }
 }
 
 impl Scope
 {
-    fn new() -> Self
+    pub fn new() -> Self
     {
-        Self { vars: util::VecMap::new(), callee: None }
+        Self { vars: util::VecMap::new(), callee: None, wrap: false }
     }
 
-    pub fn with_callee(c: Val) -> Self
+    pub fn with_callee(c: Val, wrap: bool) -> Self
     {
-        Self { vars: util::VecMap::new(), callee: Some(c) }
+        Self { vars: util::VecMap::new(), callee: Some(c), wrap: wrap }
     }
 
     pub fn print(&self)
@@ -75,10 +212,10 @@ impl Scope
 
     /******** executing functions ********/
 
-    pub fn do_block(&mut self, block: &Block) -> Option<BlockAction>
+    pub fn do_block(&mut self, block: &Block) -> RtRes<Option<BlockAction>>
     {
         if block.is_empty() { // optimizing
-            return None;
+            return Ok(None);
         }
         let presize = self.vars.size();
         let action = self.no_env_block(block);
@@ -86,98 +223,111 @@ impl Scope
         return action;
     }
 
+    // like do_block, but þe declared variables outlive it (for þe REPL)
+    pub fn do_top_block(&mut self, block: &Block)
+     -> RtRes<Option<BlockAction>>
+    {
+        return self.no_env_block(block);
+    }
+
     #[inline]
-    fn no_env_block(&mut self, block: &Block) -> Option<BlockAction>
+    fn no_env_block(&mut self, block: &Block) -> RtRes<Option<BlockAction>>
     {
         for s in block {
-            if let Some(ba) = self.do_stmt(s) {
-                return Some(ba);
+            if let Some(ba) = self.do_stmt(s).map_err(|e| e.at(s.span))? {
+                return Ok(Some(ba));
             }
         }
-        return None;
+        return Ok(None);
     }
 
-    fn do_stmt(&mut self, s: &Stmt) -> Option<BlockAction>
+    fn do_stmt(&mut self, s: &Stmt) -> RtRes<Option<BlockAction>>
     {
-        match s {
-            Stmt::Assign(v, e)    => self.do_assign(v, e),
-            Stmt::OperOn(l, o, e) => self.do_operon(l, o, e),
-            Stmt::IfElse(i, o, e) => return self.do_ifelse(i, o, e),
-            Stmt::Switch(..)      => todo!(),
-            Stmt::LoopIf(l)       => return self.do_loopif(l),
-            Stmt::AgainL(l)       => return Some(BlockAction::Loo(*l, true)),
-            Stmt::BreakL(l)       => return Some(BlockAction::Loo(*l, false)),
-            Stmt::Return(e)       => return Some(BlockAction::Ret(
-                self.eval_expr(e)
-            )),
-            Stmt::PcExit          => return Some(BlockAction::End),
-            Stmt::PcCall(p, a)    => self.do_pccall(p, a),
-            Stmt::TbPCal(..)      => todo!(),
+        match &s.kind {
+            StmtKind::Assign(v, e)    => self.do_assign(v, e)?,
+            StmtKind::OperOn(l, o, e) => self.do_operon(l, o, e)?,
+            StmtKind::IfElse(i, o, e) => return self.do_ifelse(i, o, e),
+            StmtKind::Switch(m, c, d) => return self.do_switch(m, c, d),
+            StmtKind::LoopIf(l)       => return self.do_loopif(l),
+            StmtKind::AgainL(l) => return Ok(Some(BlockAction::Loo(*l, true))),
+            StmtKind::BreakL(l) => return Ok(Some(BlockAction::Loo(*l, false))),
+            StmtKind::Return(e)       => return Ok(Some(BlockAction::Ret(
+                self.eval_expr(e)?
+            ))),
+            StmtKind::PcExit          => return Ok(Some(BlockAction::End)),
+            StmtKind::PcCall(p, a)    => self.do_pccall(p, a)?,
+            StmtKind::TbPCal(t, p, a) => self.do_tbpcal(t, p, a)?,
         }
-        return None;
+        return Ok(None);
     }
 
     #[inline]
-    fn do_assign(&mut self, ad: &Expr, ex: &Expr)
+    fn do_assign(&mut self, ad: &Expr, ex: &Expr) -> RtRes<()>
     {
-        match ad {
-            Expr::Ident(i) =>
+        match &ad.kind {
+            ExprKind::Ident(i) =>
                 self.do_var_ass(i, ex),
-            Expr::BinOp(a, BinOpcode::Idx, i) =>
-                self.do_arr_ass(a, i, ex),
-            Expr::TblFd(t, f) =>
-                self.do_tbl_ass(t, f, ex),
-            _ => panic!("cannot assign to {ad:?}"),
+            ExprKind::BinOp(a, BinOpcode::Idx, i) =>
+                self.do_arr_ass(a, i, ex).map_err(|e| e.at(ad.span)),
+            ExprKind::TblFd(t, f) =>
+                self.do_tbl_ass(t, f, ex).map_err(|e| e.at(ad.span)),
+            _ => rt_err!(Type, "cannot assign to {:?}", ad.kind),
         }
     }
 
     // v = e.
     #[inline]
-    fn do_var_ass(&mut self, v: &Rc<DfStr>, e: &Expr)
+    fn do_var_ass(&mut self, v: &Rc<DfStr>, e: &Expr) -> RtRes<()>
     {
-        self.declar(v, self.eval_expr(e));
+        let val = self.eval_expr(e)?;
+        self.declar(v, val);
+        return Ok(());
     }
 
     // a_i = e.
-    fn do_arr_ass(&self, a: &Expr, i: &Expr, e: &Expr)
+    fn do_arr_ass(&self, a: &Expr, i: &Expr, e: &Expr) -> RtRes<()>
     {
-        let Val::A(arr) = self.eval_expr(a) else {
-            panic!("not indexable");
+        let av = self.eval_expr(a)?;
+        let Val::A(arr) = av else {
+            return rt_err!(Type, "cannot index a {} value", Type::from(&av));
         };
-        let idx: u32 = match self.eval_expr(i) {
-            Val::N(n) => n,
-            Val::Z(z) => u32::try_from(z)
-                .expect("ERROR: negative index"),
-            _ => panic!("ERROR: index is not N% or Z%"),
-        };
-        let e_val = self.eval_expr(e);
-        arr.borrow_mut().try_set(idx as usize, e_val).unwrap();
+        let idx = self.eval_index(i)?;
+        let e_val = self.eval_expr(e)?;
+        let res = arr.borrow_mut().try_set(idx as usize, e_val);
+        return res.map_err(|s| RuntimeError::new(RtErrKind::Index, s));
     }
 
     // t$f = e.
-    fn do_tbl_ass(&self, t: &Expr, f: &Rc<DfStr>, e: &Expr)
+    fn do_tbl_ass(&self, t: &Expr, f: &Rc<DfStr>, e: &Expr) -> RtRes<()>
     {
-        let Val::T(mut t) = self.eval_expr(t) else {
-            panic!("not a table");
+        let tv = self.eval_expr(t)?;
+        let Val::T(mut t) = tv else {
+            return rt_err!(Type, "{tv} is not a table");
         };
-        let e_val = self.eval_expr(e);
+        if let Table::Nat(n) = t {
+            return rt_err!(Type, "cannot change native table {}", n.name());
+        }
+        let e_val = self.eval_expr(e)?;
         t.set(f, e_val);
+        return Ok(());
     }
 
     #[inline]
     fn do_operon(&mut self, lhs: &Expr, op: &BinOpcode, ex: &Expr)
+     -> RtRes<()>
     {
-        let val = self.eval_binop(lhs, op, ex);
-        self.do_assign(lhs, &Expr::Const(val));
+        let val = self.eval_binop(lhs, op, ex)?;
+        return self.do_assign(lhs, &Expr::new(ExprKind::Const(val), ex.span));
     }
 
     // helper for do_ifstmt & do_loopif
     #[inline]
-    fn eval_cond(&self, cd: &Expr) -> bool
+    fn eval_cond(&self, cd: &Expr) -> RtRes<bool>
     {
-        match self.eval_expr(cd) {
-            Val::B(b) => b,
-            _ => panic!("condition is not B%"),
+        match self.eval_expr(cd)? {
+            Val::B(b) => Ok(b),
+            v => rt_err!(Type, "condition is {}, not B%", Type::from(&v))
+                .map_err(|e: RuntimeError| e.at(cd.span)),
         }
     }
 
@@ -186,11 +336,11 @@ impl Scope
         ic: &IfCase,
         ei: &[IfCase],
         eb: &Option<Block>)
-     -> Option<BlockAction>
+     -> RtRes<Option<BlockAction>>
     {
         macro_rules! do_ifcase {
             ($zelf:ident, $c:ident) => {
-                if $zelf.eval_cond(&$c.cond) {
+                if $zelf.eval_cond(&$c.cond)? {
                     return $zelf.do_block(&$c.blok);
                 }
             }
@@ -201,11 +351,25 @@ impl Scope
         }
         return match eb {
             Some(b) => self.do_block(b),
-            None => None,
+            None => Ok(None),
         };
     }
 
-    fn do_loopif(&mut self, lo: &Loop) -> Option<BlockAction>
+    // Pascal-like: þe 1st matching case & no fallþrough, else þe default
+    fn do_switch(&mut self, mat: &Expr, cases: &[SwCase], def: &Block)
+     -> RtRes<Option<BlockAction>>
+    {
+        let m = self.eval_expr(mat)?;
+        for c in cases {
+            // eval'd one by one, only until one matches
+            if sw_matches(&m, &self.eval_expr(&c.comp)?) {
+                return self.do_block(&c.blok);
+            }
+        }
+        return self.do_block(def);
+    }
+
+    fn do_loopif(&mut self, lo: &Loop) -> RtRes<Option<BlockAction>>
     {
         let pre = self.vars.size();
         //self.enter_loop(lo); // preset Vs
@@ -217,12 +381,12 @@ impl Scope
         return ba;
     }
 
-    fn do_inf_loop(&mut self, block: &Block) -> Option<BlockAction>
+    fn do_inf_loop(&mut self, block: &Block) -> RtRes<Option<BlockAction>>
     {
         loop {
             do_loop_block!(self, block);
         }
-        return None;
+        return Ok(None);
     }
 
     fn do_cdt_loop(
@@ -230,36 +394,79 @@ impl Scope
         blok0: &Block,
         condt: &Expr,
         blok1: &Block)
-     -> Option<BlockAction>
+     -> RtRes<Option<BlockAction>>
     {
         loop {
             do_loop_block!(self, blok0);
-            if !self.eval_cond(condt) {
+            if !self.eval_cond(condt)? {
                 break;
             }
             do_loop_block!(self, blok1);
         }
-        return None;
+        return Ok(None);
     }
 
-    fn do_pccall(&mut self, p: &Expr, a: &[Expr])
+    fn do_pccall(&mut self, p: &Expr, a: &[Expr]) -> RtRes<()>
     {
-        let pc_val = self.eval_expr(p);
-        let Val::P(p) = pc_val.clone() else {
-            panic!("cannot call procedure {pc_val}");
+        let pc_val = self.eval_expr(p)?;
+        let args = self.eval_args(a)?;
+        return self.call_proc(&pc_val, args);
+    }
+
+    // t!$p!a. calls t$p wiþ t as þe 1st arg
+    fn do_tbpcal(&mut self, t: &Expr, p: &Rc<DfStr>, a: &[Expr])
+     -> RtRes<()>
+    {
+        let (pc_val, args) = self.eval_method(t, p, a)?;
+        return self.call_proc(&pc_val, args);
+    }
+
+    fn call_proc(&self, pc_val: &Val, args: Vec<Val>) -> RtRes<()>
+    {
+        let Val::P(p) = pc_val else {
+            return rt_err!(Type, "cannot call {pc_val}, it is not a proc");
         };
-        if p.arity() != a.len() {
-            panic!("not correct arity ({}) calling {:?}", a.len(), p);
+        if p.arity() != args.len() {
+            return rt_err!(Arity, "{pc_val} takes {} args, but got {}",
+                p.arity(), args.len());
         }
-        let args = self.eval_args(a);
         match p {
-            Proc::Nat(n) => n.exec(&args),
-            Proc::Usr(u, s) => self.exec_usr_proc(&u, args, &s),
+            Proc::Nat(n) => n.exec(&args, self),
+            Proc::Usr(u, s) => self.exec_usr_proc(u, args, s),
+        }
+    }
+
+    // for meþod calls: þe subroutine t$f & þe args, starting wiþ t
+    fn eval_method(&self, t: &Expr, f: &Rc<DfStr>, a: &[Expr])
+     -> RtRes<(Val, Vec<Val>)>
+    {
+        let tbl = self.eval_expr(t)?;
+        let Val::T(trc) = &tbl else {
+            return rt_err!(Type, "{tbl} is not a table");
+        };
+        let Some(subr) = trc.get(f) else {
+            return rt_err!(Field, "table hasn't ${f}");
+        };
+        let arity = match &subr {
+            Val::F(f) => f.arity(),
+            Val::P(p) => p.arity(),
+            _ => a.len() + 1, // call_func or call_proc will complain
+        };
+        if arity != a.len() + 1 {
+            return rt_err!(Arity,
+                "${f} takes {arity} args counting þe table, but got {}",
+                a.len() + 1);
         }
+        let mut args = Vec::with_capacity(a.len() + 1);
+        args.push(tbl);
+        for e in a {
+            args.push(self.eval_expr(e)?);
+        }
+        return Ok((subr, args));
     }
 
     #[inline]
-    fn eval_args(&self, a: &[Expr]) -> Vec<Val>
+    fn eval_args(&self, a: &[Expr]) -> RtRes<Vec<Val>>
     {
         a.iter()
          .map(|b| self.eval_expr(b))
@@ -271,114 +478,123 @@ impl Scope
             subr: &MutRc<Subr>,
         mut args: Vec<Val>,
             upvs: &UpVals,
-        )
+        ) -> RtRes<()>
     {
         // Future optimization: doesn't need a callee if it's not !@
         let mut proc_scope = Scope::with_callee(
-            Val::new_usr_pc(subr.clone(), upvs.clone())
+            Val::new_usr_pc(subr.clone(), upvs.clone()),
+            self.wrap,
         );
         let subr = subr.borrow();
-        if let Some(uv) = upvs {
-            for (name, upval) in std::iter::zip(&subr.upvs, &**uv) {
-                proc_scope.declar(name, upval.clone());
-            }
+        for (name, upval) in std::iter::zip(&subr.upvs, &**upvs) {
+            proc_scope.declar(name, upval.clone());
         }
         for name in subr.pars.iter().rev() {
             let val = args.pop().unwrap(); // already checked arity
             proc_scope.declar(name, val);
         }
-        if let Some(ba) = proc_scope.do_block(&subr.body) {
-            match ba {
-                BlockAction::End => return, // ok
-                _ => panic!("cannot return or break from proc"),
-            }
+        let res = match proc_scope.do_block(&subr.body) {
+            Ok(None | Some(BlockAction::End)) => Ok(()),
+            Ok(Some(_)) => rt_err!(Control, "cannot return or break from proc"),
+            Err(e) => Err(e),
         };
-    }
-
-    fn eval_expr(&self, e: &Expr) -> Val
-    {
-        match e {
-            Expr::Const(c)       => c.clone(),
-            Expr::Ident(i)       => self.eval_ident(i),
-            Expr::Tcast(t, e)    => do_cast(t, &self.eval_expr(e)),
-            Expr::BinOp(l, o, r) => self.eval_binop(l, o, r),
-            Expr::UniOp(t, o)    => eval_uniop(&self.eval_expr(t), o),
-            Expr::CmpOp(f, o)    => self.eval_cmpop(f, o),
-            Expr::FnDef(s)       => self.eval_fndef(s),
-            Expr::Fcall(c, a)    => self.eval_fcall(c, a),
-            Expr::RecFn          => self.get_rec_f(),
-            Expr::PcDef(s)       => self.eval_pcdef(s),
-            Expr::RecPc          => self.get_rec_p(),
-            Expr::Array(a)       => self.eval_array(a),
-            Expr::Table(v)       => self.eval_table(v),
-            Expr::TblFd(e, f)    => self.eval_tblfd(e, f),
-            Expr::IfExp(c, e)    => self.eval_if_expr(c, e),
-            _ => todo!("{:?}", e),
-        }
+        return res.map_err(|e| e.inside(&subr.meta));
+    }
+
+    pub fn eval_expr(&self, e: &Expr) -> RtRes<Val>
+    {
+        let res = match &e.kind {
+            ExprKind::Const(c)       => Ok(eval_const(c)),
+            ExprKind::Ident(i)       => self.eval_ident(i),
+            ExprKind::Tcast(t, e)    => do_cast(t, &self.eval_expr(e)?),
+            ExprKind::BinOp(l, o, r) => self.eval_binop(l, o, r),
+            ExprKind::UniOp(t, o)    =>
+                eval_uniop(&self.eval_expr(t)?, o, self.wrap),
+            ExprKind::CmpOp(f, o)    => self.eval_cmpop(f, o),
+            ExprKind::FnDef(s)       => self.eval_fndef(s),
+            ExprKind::Fcall(c, a)    => self.eval_fcall(c, a),
+            ExprKind::RecFn          => self.get_rec_f(),
+            ExprKind::PcDef(s)       => self.eval_pcdef(s),
+            ExprKind::RecPc          => self.get_rec_p(),
+            ExprKind::Array(a)       => self.eval_array(a),
+            ExprKind::Table(v)       => self.eval_table(v),
+            ExprKind::TblFd(e, f)    => self.eval_tblfd(e, f),
+            ExprKind::IfExp(c, e)    => self.eval_if_expr(c, e),
+            ExprKind::TbFcl(t, f, a) => self.eval_tbfcl(t, f, a),
+            ExprKind::RecsT(_)       => todo!("{:?}", e.kind),
+        };
+        return res.map_err(|err| err.at(e.span));
     }
 
     #[inline]
-    fn eval_ident(&self, i: &Rc<DfStr>) -> Val
+    fn eval_ident(&self, i: &Rc<DfStr>) -> RtRes<Val>
     {
         // try variable
         if let Some(v) = self.vars.get(i) {
-            return v.clone();
+            return Ok(v.clone());
         }
-        panic!("cannot find {i} in scope");
+        return rt_err!(Name, "cannot find `{i}` in scope");
     }
 
     #[inline]
-    fn eval_cmpop(&self, first: &Expr, others: &[(BinOpcode, Expr)]) -> Val
+    fn eval_cmpop(&self, first: &Expr, others: &[(BinOpcode, Expr)])
+     -> RtRes<Val>
     {
-        // `a <= b < c` evals as `a <= b & b < c` but only eval `b` once
-        let term0: Val = self.eval_expr(first);
+        // `a <= b < c` evals as `a <= b &? b < c` but only eval `b` once
+        let mut lhs: Val = self.eval_expr(first)?;
         if others.is_empty() {
-            return term0;
-        }
-        let mut terms = others
-            .iter()
-            .map(|t| self.eval_expr(&t.1)) // b, c
-            .collect::<Vec<_>>();
-        terms.insert(0, term0); // a
-        return Val::B(terms
-            .windows(2)
-            .enumerate()
-            .map(|(i, w)| match eval_binop_val(&w[0], &others[i].0, &w[1]) {
-                Val::B(b) => b,
+            return Ok(lhs);
+        }
+        for (op, e) in others {
+            let rhs = self.eval_expr(e)?;
+            match eval_binop_val(&lhs, op, &rhs, self.wrap)? {
+                Val::B(true) => lhs = rhs,
+                Val::B(false) => return Ok(Val::B(false)), // short circuit
                 _ => unreachable!(), // all cmp give B%
-            })
-            .all(|e| e)
-        );
+            }
+        }
+        return Ok(Val::B(true));
     }
 
     #[inline]
-    fn eval_fndef(&self, s: &MutRc<Subr>) -> Val
+    fn eval_fndef(&self, s: &MutRc<Subr>) -> RtRes<Val>
     {
         let mut upvals = vec![];
-        let su = &s.borrow().upvs;
-        if su.is_empty() {
-            return Val::new_usr_fn(s.clone(), None);
-        }
         for name in &s.borrow().upvs {
-            upvals.push(self.eval_ident(name));
+            upvals.push(self.eval_ident(name)?);
         }
-        return Val::new_usr_fn(s.clone(), Some(Rc::new(upvals)));
+        // a new Rc even if empty, so each evaluation has its own identity
+        return Ok(Val::new_usr_fn(s.clone(), Rc::new(upvals)));
+    }
+
+    #[inline]
+    fn eval_fcall(&self, f: &Expr, a: &[Expr]) -> RtRes<Val>
+    {
+        let fn_val = self.eval_expr(f)?;
+        let args = self.eval_args(a)?;
+        return self.call_func(&fn_val, args);
     }
 
+    // t#$f#a; calls t$f wiþ t as þe 1st arg
     #[inline]
-    fn eval_fcall(&self, f: &Expr, a: &[Expr]) -> Val
+    fn eval_tbfcl(&self, t: &Expr, f: &Rc<DfStr>, a: &[Expr]) -> RtRes<Val>
+    {
+        let (fn_val, args) = self.eval_method(t, f, a)?;
+        return self.call_func(&fn_val, args);
+    }
+
+    fn call_func(&self, fn_val: &Val, args: Vec<Val>) -> RtRes<Val>
     {
-        let fn_val = self.eval_expr(f);
-        let Val::F(f) = fn_val.clone() else {
-            panic!("cannot call a non-function {fn_val}");
+        let Val::F(f) = fn_val else {
+            return rt_err!(Type, "cannot call {fn_val}, it is not a func");
         };
-        if f.arity() != a.len() {
-            panic!("not correct arity ({}) calling {}", a.len(), fn_val);
+        if f.arity() != args.len() {
+            return rt_err!(Arity, "{fn_val} takes {} args, but got {}",
+                f.arity(), args.len());
         }
-        let args = self.eval_args(a);
         match f {
-            Func::Usr(u, s) => self.eval_usr_func(&u, args, &s),
-            Func::Nat(n) => n.eval(&args).unwrap(),
+            Func::Usr(u, s) => self.eval_usr_func(u, args, s),
+            Func::Nat(n) => n.eval(&args, self),
         }
     }
 
@@ -387,32 +603,32 @@ impl Scope
             subr: &MutRc<Subr>,
         mut args: Vec<Val>,
             upvs: &UpVals,
-        ) -> Val
+        ) -> RtRes<Val>
     {
         // Future optimization: doesn't need a callee if it's not #@
         let mut func_scope = Scope::with_callee(
-            Val::new_usr_fn(subr.clone(), upvs.clone())
+            Val::new_usr_fn(subr.clone(), upvs.clone()),
+            self.wrap,
         );
         let subr = subr.borrow();
-        if let Some(uv) = upvs {
-            for (name, upval) in std::iter::zip(&subr.upvs, &**uv) {
-                func_scope.declar(name, upval.clone());
-            }
+        for (name, upval) in std::iter::zip(&subr.upvs, &**upvs) {
+            func_scope.declar(name, upval.clone());
         }
         for name in subr.pars.iter().rev() {
             let val = args.pop().unwrap(); // already checked arity
             func_scope.declar(name, val);
         }
-        let Some(ba) = func_scope.do_block(&subr.body) else {
-            panic!("ended function w/o returning a value");
+        let res = match func_scope.do_block(&subr.body) {
+            Ok(Some(BlockAction::Ret(v))) => Ok(v),
+            Ok(None) => rt_err!(Control,
+                "ended function w/o returning a value"),
+            Ok(Some(_)) => rt_err!(Control, "cannot exit or break from func"),
+            Err(e) => Err(e),
         };
-        match ba {
-            BlockAction::Ret(v) => return v,
-            _ => panic!("cannot return or break from func"),
-        }
+        return res.map_err(|e| e.inside(&subr.meta));
     }
 
-    fn eval_binop(&self, l: &Expr, o: &BinOpcode, r: &Expr) -> Val
+    fn eval_binop(&self, l: &Expr, o: &BinOpcode, r: &Expr) -> RtRes<Val>
     {
         if o.is_sce() { // short circuit
             self.eval_sce(l, o, r)
@@ -420,126 +636,133 @@ impl Scope
             self.eval_arr_idx(l, r)
         } else { // classic binops
             eval_binop_val(
-                &self.eval_expr(l),
+                &self.eval_expr(l)?,
                 o,
-                &self.eval_expr(r),
+                &self.eval_expr(r)?,
+                self.wrap,
             )
         }
     }
 
     // Short Circuit Evaluation: l must be B, r can be any value
-    fn eval_sce(&self, l: &Expr, o: &BinOpcode, r: &Expr) -> Val
+    fn eval_sce(&self, l: &Expr, o: &BinOpcode, r: &Expr) -> RtRes<Val>
     {
-        let lval = self.eval_expr(l);
+        let lval = self.eval_expr(l)?;
         let Val::B(lval) = lval else {
-            panic!("lhs value of {lval} is not B%");
+            return rt_err!(Type, "lhs value of {lval} is not B%");
         };
         match o {
             BinOpcode::Cand => if lval {
                 self.eval_expr(r)
             } else {
-                Val::B(false)
+                Ok(Val::B(false))
             },
             BinOpcode::Cor => if !lval {
                 self.eval_expr(r)
             } else {
-                Val::B(true)
+                Ok(Val::B(true))
             },
             _ => unreachable!(),
         }
     }
 
-    fn eval_arr_idx(&self, a: &Expr, i: &Expr) -> Val
+    // N% or non-negative Z%
+    fn eval_index(&self, i: &Expr) -> RtRes<u32>
     {
-        let Val::A(a_val) = self.eval_expr(a) else {
-            panic!("ERROR: {a:?} is not indexable (must _%)");
-        };
-        let ival = self.eval_expr(i);
-        let i_val = match ival {
-            Val::N(n) => n,
-            Val::Z(z) => u32::try_from(z)
-                .expect("ERROR: negative index"),
-            _ => panic!("cannot use {ival} as index"),
+        match self.eval_expr(i)? {
+            Val::N(n) => Ok(n),
+            Val::Z(z) => u32::try_from(z).map_err(|_| RuntimeError::new(
+                RtErrKind::Index, format!("negative index {z}"))),
+            v => rt_err!(Type, "cannot use {v} as index, must be N% or Z%"),
+        }
+    }
+
+    fn eval_arr_idx(&self, a: &Expr, i: &Expr) -> RtRes<Val>
+    {
+        let av = self.eval_expr(a)?;
+        let Val::A(a_val) = av else {
+            return rt_err!(Type, "cannot index a {} value", Type::from(&av));
         };
+        let i_val = self.eval_index(i)?;
         let a_ref = a_val.borrow();
         match a_ref.get(i_val as usize) {
-            Some(v) => v,
-            None => panic!("{} out of bounds (len = {})",
+            Some(v) => Ok(v),
+            None => rt_err!(Index, "{} out of bounds (len = {})",
                 i_val, a_ref.len()),
         }
     }
 
     #[inline]
-    fn eval_pcdef(&self, s: &MutRc<Subr>) -> Val
+    fn eval_pcdef(&self, s: &MutRc<Subr>) -> RtRes<Val>
     {
         let mut upvals = vec![];
-        let su = &s.borrow().upvs;
-        if su.is_empty() {
-            return Val::new_usr_pc(s.clone(), None);
-        }
         for name in &s.borrow().upvs {
-            upvals.push(self.eval_ident(name));
+            upvals.push(self.eval_ident(name)?);
         }
-        return Val::new_usr_pc(s.clone(), Some(Rc::new(upvals)));
+        // a new Rc even if empty, so each evaluation has its own identity
+        return Ok(Val::new_usr_pc(s.clone(), Rc::new(upvals)));
     }
 
     #[inline]
-    fn get_rec_f(&self) -> Val
+    fn get_rec_f(&self) -> RtRes<Val>
     {
         if let Some(v) = &self.callee {
             if Type::from(v) == Type::F {
-                return v.clone();
+                return Ok(v.clone());
             }
         }
-        panic!("cannot refer #@ not inside a func");
+        return rt_err!(Control, "cannot refer #@ not inside a func");
     }
 
     #[inline]
-    fn get_rec_p(&self) -> Val
+    fn get_rec_p(&self) -> RtRes<Val>
     {
         if let Some(v) = &self.callee {
             if Type::from(v) == Type::P {
-                return v.clone();
+                return Ok(v.clone());
             }
         }
-        panic!("cannot refer !@ not inside a proc");
+        return rt_err!(Control, "cannot refer !@ not inside a proc");
     }
 
     #[inline]
-    fn eval_array(&self, a: &[Expr]) -> Val
+    fn eval_array(&self, a: &[Expr]) -> RtRes<Val>
     {
-        Val::from_array(self.eval_args(a)
-            .as_slice()
-            .try_into()
-            .unwrap()
-        )
+        let vals = self.eval_args(a)?;
+        return match Array::try_from(vals.as_slice()) {
+            Ok(arr) => Ok(Val::from_array(arr)),
+            Err(s) => rt_err!(Type, "{s}"),
+        };
     }
 
-    fn eval_table(&self, e: &[(Rc<DfStr>, Expr)]) -> Val
+    fn eval_table(&self, e: &[(Rc<DfStr>, Expr)]) -> RtRes<Val>
     {
         let mut t = Table::new_empty();
         // TODO eke $@
         for (k, ve) in e {
-            let v = self.eval_expr(ve);
+            let v = self.eval_expr(ve)?;
             t.set(k, v);
         }
-        return Val::T(t);
+        return Ok(Val::T(t));
     }
 
-    fn eval_tblfd(&self, t: &Expr, f: &DfStr) -> Val
+    fn eval_tblfd(&self, t: &Expr, f: &DfStr) -> RtRes<Val>
     {
-        let tbl = self.eval_expr(t);
+        let tbl = self.eval_expr(t)?;
         let Val::T(trc) = tbl else {
-            panic!("{tbl} is not a table");
+            return rt_err!(Type, "{tbl} is not a table");
         };
-        return trc.get(f).expect(&format!("table hasn't ${f}"));
+        return trc.get(f).map_or_else(
+            || rt_err!(Field, "table hasn't ${f}"),
+            Ok,
+        );
     }
 
     #[inline]
-    fn eval_if_expr(&self, cases: &[(Expr, Expr)], elze: &Expr) -> Val
+    fn eval_if_expr(&self, cases: &[(Expr, Expr)], elze: &Expr) -> RtRes<Val>
     {
         for (cond, res) in cases {
-            if self.eval_cond(cond) {
+            if self.eval_cond(cond)? {
                 return self.eval_expr(res);
             }
         }
@@ -547,140 +770,149 @@ impl Scope
     }
 }
 
-fn do_cast(t: &Type, v: &Val) -> Val
+// like þe VM's JNX: different types just don't match, & R% is exact
+#[allow(clippy::float_cmp)]
+fn sw_matches(m: &Val, c: &Val) -> bool
 {
-    if *t == Type::from(v) {
-        return (*v).clone();
-    }
-    return match (v, t) {
-        (Val::N(n), Type::Z) => Val::Z(*n as i32),
-        (Val::Z(z), Type::R) => Val::R(*z as f32),
-        (Val::N(n), Type::R) => Val::R(*n as f32),
-        // dangerous casts
-        (Val::Z(z), Type::N) =>
-            if *z < 0 {
-                panic!("converting negative Z% to N%")
-            } else {
-                Val::N(*z as u32)
-            },
-        _ => panic!("converting types"),
+    match (m, c) {
+        (Val::R(r), Val::R(s)) => r == s,
+        _ => m == c,
     }
 }
 
-fn eval_uniop(t: &Val, o: &UniOpcode) -> Val
+fn do_cast(t: &Type, v: &Val) -> RtRes<Val>
+{
+    return v.cast(*t).map_err(|s| RuntimeError::new(RtErrKind::Cast, s));
+}
+
+fn eval_uniop(t: &Val, o: &UniOpcode, wrap: bool) -> RtRes<Val>
 {
     match o {
-        UniOpcode::Neg => match t {
-            Val::Z(z) => return Val::Z(-(*z)),
-            Val::R(r) => return Val::R(-(*r)),
-            _ => panic!("can only sub (-) a Z% or R% value"),
+        UniOpcode::Neg => match t.neg(wrap) {
+            Some(res) =>
+                res.map_err(|s| RuntimeError::new(RtErrKind::Arith, s)),
+            None => rt_err!(Type, "can only sub (-) a Z% or R% value"),
         }
         UniOpcode::Inv => match t {
-            Val::R(r) => return Val::R(1.0/(*r)),
-            _ => panic!("can only invert (/) a R% value"),
+            Val::R(r) => Ok(Val::R(1.0/(*r))),
+            _ => rt_err!(Type, "can only invert (/) a R% value"),
         }
         UniOpcode::Not => match t {
-            Val::B(b) => return Val::B(!(*b)),
-            Val::N(n) => return Val::N(!(*n)),
-            _ => panic!("~ is only for B% & N% values"),
+            Val::B(b) => Ok(Val::B(!(*b))),
+            Val::N(n) => Ok(Val::N(!(*n))),
+            Val::Z(z) => Ok(Val::Z(!(*z))),
+            _ => rt_err!(Type, "~ is only for B%, N% & Z% values"),
         }
     }
 }
 
-fn eval_binop_val(l: &Val, o: &BinOpcode, r: &Val) -> Val
+// an array literal gives a new array each time, not þe constant itself
+fn eval_const(c: &Val) -> Val
 {
-    if *o == BinOpcode::Mod { match (l, r) {
-        (Val::N(vl), Val::N(vr)) => return Val::N(vl % vr),
-        (Val::Z(vl), Val::N(vr)) => {
-            let vrz = *vr as i32;
-            return Val::N(((vl % vrz + vrz) % vrz).try_into().unwrap());
-        },
-        _ => panic!("invalid types in mod op"),
-    }}
+    match c {
+        Val::A(a) => Val::from_array(a.borrow().clone()),
+        _ => c.clone(),
+    }
+}
+
+fn eval_binop_val(lhs: &Val, op: &BinOpcode, rhs: &Val, wrap: bool)
+ -> RtRes<Val>
+{
+    let lt: Type = lhs.into();
+    let rt: Type = rhs.into();
+
+    // N% & Z% +, -, *, /, \, *^, << & >>
+    if let Some(res) = lhs.int_arith(*op, rhs, wrap) {
+        return res.map_err(|s| RuntimeError::new(RtErrKind::Arith, s));
+    }
+    if *op == BinOpcode::Mod {
+        return rt_err!(Type, "invalid types in mod op: {lt} and {rt}");
+    }
 
-    let lt: Type = l.into();
-    let rt: Type = r.into();
     if lt != rt {
-        panic!("operating different types");
+        return rt_err!(Type, "operating different types: {lt} and {rt}");
     }
 
     // first, check cmp operations
-    match o {
+    match op {
         // equivalence cmp only btwin þe same type
-        BinOpcode::Eq => return match (l, r) {
-            (Val::R(_), Val::R(_)) => panic!("use an epsilon wiþ R% u idiot"),
-            _ => Val::B(l == r),
+        BinOpcode::Eq => return match (lhs, rhs) {
+            (Val::R(_), Val::R(_)) =>
+                rt_err!(Equality, "use an epsilon wiþ R% u idiot"),
+            _ => Ok(Val::B(lhs == rhs)),
         },
-        BinOpcode::Ne => return match (l, r) {
-            (Val::R(_), Val::R(_)) => panic!("use an epsilon wiþ R% u idiot"),
-            _ => Val::B(l != r),
+        BinOpcode::Ne => return match (lhs, rhs) {
+            (Val::R(_), Val::R(_)) =>
+                rt_err!(Equality, "use an epsilon wiþ R% u idiot"),
+            _ => Ok(Val::B(lhs != rhs)),
         },
         // order cmp only btwin numerical types
         // TODO: impl PartialOrd for Val { if is_num }
-        BinOpcode::Lt => match (l, r) {
-            (Val::N(vl), Val::N(vr)) => return Val::B(vl < vr),
-            (Val::Z(vl), Val::Z(vr)) => return Val::B(vl < vr),
-            (Val::R(vl), Val::R(vr)) => return Val::B(vl < vr),
-            _ => panic!("comparing different types"),
-        }
-        BinOpcode::Gt => match (l, r) {
-            (Val::N(vl), Val::N(vr)) => return Val::B(vl > vr),
-            (Val::Z(vl), Val::Z(vr)) => return Val::B(vl > vr),
-            (Val::R(vl), Val::R(vr)) => return Val::B(vl > vr),
-            _ => panic!("comparing different types"),
-        }
-        BinOpcode::Le => match (l, r) {
-            (Val::N(vl), Val::N(vr)) => return Val::B(vl <= vr),
-            (Val::Z(vl), Val::Z(vr)) => return Val::B(vl <= vr),
-            (Val::R(vl), Val::R(vr)) => return Val::B(vl <= vr),
-            _ => panic!("comparing different types"),
-        }
-        BinOpcode::Ge => match (l, r) {
-            (Val::N(vl), Val::N(vr)) => return Val::B(vl >= vr),
-            (Val::Z(vl), Val::Z(vr)) => return Val::B(vl >= vr),
-            (Val::R(vl), Val::R(vr)) => return Val::B(vl >= vr),
-            _ => panic!("comparing different types"),
-        }
+        BinOpcode::Lt => return match (lhs, rhs) {
+            (Val::N(vl), Val::N(vr)) => Ok(Val::B(vl < vr)),
+            (Val::Z(vl), Val::Z(vr)) => Ok(Val::B(vl < vr)),
+            (Val::R(vl), Val::R(vr)) => Ok(Val::B(vl < vr)),
+            _ => rt_err!(Type, "cannot compare {lt} values"),
+        },
+        BinOpcode::Gt => return match (lhs, rhs) {
+            (Val::N(vl), Val::N(vr)) => Ok(Val::B(vl > vr)),
+            (Val::Z(vl), Val::Z(vr)) => Ok(Val::B(vl > vr)),
+            (Val::R(vl), Val::R(vr)) => Ok(Val::B(vl > vr)),
+            _ => rt_err!(Type, "cannot compare {lt} values"),
+        },
+        BinOpcode::Le => return match (lhs, rhs) {
+            (Val::N(vl), Val::N(vr)) => Ok(Val::B(vl <= vr)),
+            (Val::Z(vl), Val::Z(vr)) => Ok(Val::B(vl <= vr)),
+            (Val::R(vl), Val::R(vr)) => Ok(Val::B(vl <= vr)),
+            _ => rt_err!(Type, "cannot compare {lt} values"),
+        },
+        BinOpcode::Ge => return match (lhs, rhs) {
+            (Val::N(vl), Val::N(vr)) => Ok(Val::B(vl >= vr)),
+            (Val::Z(vl), Val::Z(vr)) => Ok(Val::B(vl >= vr)),
+            (Val::R(vl), Val::R(vr)) => Ok(Val::B(vl >= vr)),
+            _ => rt_err!(Type, "cannot compare {lt} values"),
+        },
         _ => {}, // continue
     }
 
     // then, check num & bool operations
-    return match (l, r) {
-        (Val::B(vl), Val::B(vr)) => match o {
+    let res = match (lhs, rhs) {
+        (Val::B(vl), Val::B(vr)) => match op {
             BinOpcode::And => Val::B(*vl && *vr),
             BinOpcode::Or  => Val::B(*vl || *vr),
             BinOpcode::Xor => Val::B(*vl ^ *vr),
-            _ => panic!("unknown op btwin B%"),
+            _ => return rt_err!(Type, "not valid operation btwin B%"),
         },
-        (Val::N(vl), Val::N(vr)) => match o {
-            BinOpcode::Add => Val::N(vl + vr),
-            BinOpcode::Mul => Val::N(vl * vr),
+        (Val::N(vl), Val::N(vr)) => match op {
             BinOpcode::And => Val::N(*vl & *vr),
             BinOpcode::Or  => Val::N(*vl | *vr),
             BinOpcode::Xor => Val::N(*vl ^ *vr),
-            _ => panic!("not valid operation btwin N%"),
+            _ => return rt_err!(Type, "not valid operation btwin N%"),
         },
-        (Val::Z(vl), Val::Z(vr)) => match o {
-            BinOpcode::Add => Val::Z(vl + vr),
-            BinOpcode::Sub => Val::Z(vl - vr),
-            BinOpcode::Mul => Val::Z(vl * vr),
-            _ => panic!("not valid operation btwin Z%"),
+        (Val::Z(vl), Val::Z(vr)) => match op {
+            BinOpcode::And => Val::Z(*vl & *vr),
+            BinOpcode::Or  => Val::Z(*vl | *vr),
+            BinOpcode::Xor => Val::Z(*vl ^ *vr),
+            _ => return rt_err!(Type, "not valid operation btwin Z%"),
         },
-        (Val::R(vl), Val::R(vr)) => match o {
+        (Val::R(vl), Val::R(vr)) => match op {
             BinOpcode::Add => Val::R(vl + vr),
             BinOpcode::Sub => Val::R(vl - vr),
             BinOpcode::Mul => Val::R(vl * vr),
             BinOpcode::Div => Val::R(vl / vr),
-            _ => panic!("not valid operation btwin R%"),
+            BinOpcode::Pow => Val::R(vl.powf(*vr)),
+            _ => return rt_err!(Type, "not valid operation btwin R%"),
         },
-        (Val::A(a), Val::A(b)) => match o {
-            BinOpcode::Add => Val::from_array(a.borrow().add(&b.borrow())
-                .unwrap()),
-            _ => panic!("not valid operation btwin _%"),
-
+        (Val::A(la), Val::A(ra)) => match op {
+            BinOpcode::Add => match la.borrow().add(&ra.borrow()) {
+                Ok(cat) => Val::from_array(cat),
+                Err(msg) => return rt_err!(Type, "{msg}"),
+            },
+            _ => return rt_err!(Type, "not valid operation btwin _%"),
         },
-        _ => panic!("not valid operation btwin {l:?} and {r:?}"),
-    }
+        _ => return rt_err!(Type, "not valid operation btwin {lt} values"),
+    };
+    return Ok(res);
 }
 
 #[derive(Debug, Clone)]
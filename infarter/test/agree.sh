@@ -0,0 +1,80 @@
+#!/bin/sh
+
+# runs every .df on þe tree-walk interpreter & on FlatVM (at every -O level)
+# & checks þat boþ print þe same
+# usage: ./agree.sh [-r] [path/to/flatvm]
+
+bin="../target/debug/infarter"
+vm="../../flatvm/flatvm"
+
+if [ "$1" = "-r" ]; # --release
+then
+    bin="../target/release/infarter"
+    shift
+fi
+
+if [ -n "$1" ];
+then
+    vm="$1"
+fi
+
+echo $bin $vm
+
+if [ ! -x "$vm" ];
+then
+    echo "no FlatVM at $vm, build it first"
+    exit 1
+fi
+
+tmp=$(mktemp)
+out0=$(mktemp)
+out1=$(mktemp)
+
+# þese must end wiþ an error, on boþ
+fails="natTblAss.df stdinLatin1.df"
+
+for f in $(ls *.df)
+do
+    # stdin comes from foo.in, if þer's one for foo.df
+    in="${f%.df}.in"
+    [ -f $in ] || in=/dev/null
+    case " $fails " in
+      *" $f "*) want=1 ;;
+      *) want=0 ;;
+    esac
+    $bin $f < $in > $out0 2> /dev/null
+    rc=$?
+    if [ $rc -ne 0 ] && [ $want -eq 0 ];
+    then
+        echo "SKIP $f (fails on infarter)"
+        continue
+    elif [ $rc -eq 0 ] && [ $want -eq 1 ];
+    then
+        echo "SKIP $f (doesn't fail on infarter)"
+        continue
+    fi
+    for o in -O0 -O1 -O2
+    do
+        if ! $bin compile $o -o $tmp $f > /dev/null 2> /dev/null;
+        then
+            echo "SKIP $f $o (does not compile)"
+            continue
+        fi
+        $vm $tmp < $in > $out1 2> /dev/null
+        rc=$?
+        if [ $rc -ne 0 ] && [ $want -eq 0 ];
+        then
+            echo "FAIL $f $o (fails on FlatVM)"
+        elif [ $rc -eq 0 ] && [ $want -eq 1 ];
+        then
+            echo "FAIL $f $o (doesn't fail on FlatVM)"
+        elif [ $want -eq 1 ] || cmp -s $out0 $out1; # error output differs
+        then
+            echo "PASS $f $o"
+        else
+            echo "FAIL $f $o (different output)"
+        fi
+    done
+done
+
+rm -f $tmp $out0 $out1
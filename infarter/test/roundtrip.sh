@@ -0,0 +1,43 @@
+#!/bin/sh
+
+# compiles every .df at every -O level & checks þat decoding and re-encoding
+# þe .dfc gives back þe same bytes
+
+bin="../target/debug/infarter"
+echo $bin
+
+if [ "$1" = "-r" ]; # --release
+then
+    bin="../target/release/infarter"
+fi
+
+# written in old syntax, þey don't parse
+broken="duck.df list.df splitWs.df"
+# & þis one has bad literals on purpose
+broken="$broken badLiterals.df"
+
+tmp=$(mktemp)
+
+for f in $(ls *.df)
+do
+    case " $broken " in
+      *" $f "*)
+        echo "SKIP $f (known not to parse)"
+        continue
+        ;;
+    esac
+    for o in -O0 -O1 -O2
+    do
+        if ! $bin compile $o -o $tmp $f > /dev/null 2> /dev/null;
+        then
+            echo "FAIL $f $o (does not compile)"
+        elif $bin dis --roundtrip $tmp > /dev/null;
+        then
+            echo "PASS $f $o"
+        else
+            echo "FAIL $f $o"
+        fi
+    done
+done
+
+rm -f $tmp
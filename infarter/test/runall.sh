@@ -8,9 +8,22 @@ then
     bin="../target/release/infarter"
 fi
 
+# þese must end wiþ an error
+fails="badLiterals.df natTblAss.df stdinLatin1.df"
+# & þese are written in old syntax, þey don't parse (as in roundtrip.sh)
+fails="$fails duck.df list.df splitWs.df"
+
 for f in $(ls *.df)
 do
-    $bin $f > /dev/null 2> /dev/null
+    # stdin comes from foo.in, if þer's one for foo.df
+    in="${f%.df}.in"
+    [ -f $in ] || in=/dev/null
+    $bin $f < $in > /dev/null 2> /dev/null
+    rc=$?
+    case " $fails " in
+      *" $f "*) [ $rc -ne 0 ] ;;
+      *) [ $rc -eq 0 ] ;;
+    esac
     if [ $? -eq 0 ];
     then
         echo "PASS $f"